@@ -1,8 +1,10 @@
+use std::convert::Infallible;
 use std::path::PathBuf;
 use std::sync::Arc;
 
 use clap::Parser;
 use example_yew_ssr::{ServerApp, ServerAppProps};
+use futures::stream::{self, Stream, StreamExt};
 use stylist::manager::{render_static, StyleManager};
 use warp::Filter;
 
@@ -14,8 +16,15 @@ struct Opt {
     dir: PathBuf,
 }
 
-async fn render(index_html_s: Arc<str>) -> String {
+fn render(index_html_s: Arc<str>) -> impl Stream<Item = Result<String, Infallible>> + Send {
     let (writer, reader) = render_static();
+    let reader = reader.into_streaming();
+
+    let (index_html_before, index_html_after) = index_html_s
+        .split_once("<!--%BODY_PLACEHOLDER%-->")
+        .expect("failed to find body placeholder");
+    let (index_html_before, index_html_after) =
+        (index_html_before.to_string(), index_html_after.to_string());
 
     let body_s = yew::ServerRenderer::<ServerApp>::with_props(move || {
         let manager = StyleManager::builder()
@@ -24,18 +33,34 @@ async fn render(index_html_s: Arc<str>) -> String {
             .expect("failed to create style manager.");
         ServerAppProps { manager }
     })
-    .render()
-    .await;
+    .render_stream()
+    .boxed();
 
-    let data = reader.read_style_data();
+    // Styles are written into the body as they are created, ahead of the markup that uses them.
+    let body_s = stream::unfold(Some((body_s, reader)), |state| async move {
+        let (mut body_s, mut reader) = state?;
+        let mut s = String::new();
 
-    let mut style_s = String::new();
-    data.write_static_markup(&mut style_s)
-        .expect("failed to read styles from style manager");
+        match body_s.next().await {
+            Some(chunk) => {
+                reader
+                    .write_chunk(&mut s, &chunk)
+                    .expect("failed to write styles");
+                Some((s, Some((body_s, reader))))
+            }
+            None => {
+                reader
+                    .write_pending(&mut s)
+                    .expect("failed to write styles");
+                Some((s, None))
+            }
+        }
+    });
 
-    index_html_s
-        .replace("<!--%BODY_PLACEHOLDER%-->", &body_s)
-        .replace("<!--%HEAD_PLACEHOLDER%-->", &style_s)
+    stream::once(async move { index_html_before })
+        .chain(body_s)
+        .chain(stream::once(async move { index_html_after }))
+        .map(Ok)
 }
 
 #[tokio::main]
@@ -52,7 +77,7 @@ async fn main() {
     let render_f = warp::get().then(move || {
         let index_html_s = index_html_s.clone();
 
-        async move { warp::reply::html(render(index_html_s).await) }
+        async move { warp::reply::html(warp::hyper::Body::wrap_stream(render(index_html_s))) }
     });
 
     let routes = warp::path::end()
@@ -1,53 +1,142 @@
 use std::borrow::Cow;
+use std::hash::{Hash, Hasher};
 
 use serde::{Deserialize, Serialize};
 
-use super::{RuleBlockContent, Selector, StyleContext, ToStyleStr};
+use super::{RuleBlockContent, Selector, Span, StyleContext, ToStyleStr};
 
 /// A block is a set of css properties that apply to elements that
 /// match the condition. The CSS standard calls these "Qualified rules".
 ///
+/// Blocks can be nested in other blocks, in which case the selectors are resolved against the
+/// selectors of the parent block.
+///
 /// E.g.:
 /// ```css
 /// .inner {
 ///     color: red;
+///
+///     &:hover {
+///         color: blue;
+///     }
 /// }
 /// ```
-#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Debug, Clone, Eq, Serialize, Deserialize)]
 pub struct Block {
     /// Selector(s) for Current Block
     ///
-    /// If the value is set as [`&[]`], it signals to substitute with the classname generated for
-    /// the [`Sheet`](super::Sheet) in which this is contained.
+    /// If the value is set as [`&[]`], it signals to substitute with the selectors of the parent
+    /// block or the classname generated for the [`Sheet`](super::Sheet) in which this is
+    /// contained.
     pub condition: Cow<'static, [Selector]>,
     pub content: Cow<'static, [RuleBlockContent]>,
+    /// The position of this block in the source, if it is parsed at runtime.
+    #[serde(skip)]
+    pub span: Option<Span>,
+}
+
+// Spans are ignored, so blocks parsed from different sources are registered as the same style.
+impl PartialEq for Block {
+    fn eq(&self, other: &Self) -> bool {
+        self.condition == other.condition && self.content == other.content
+    }
+}
+
+impl Hash for Block {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.condition.hash(state);
+        self.content.hash(state);
+    }
 }
 
 impl Block {
-    fn cond_str(&self, ctx: &mut StyleContext<'_>) -> Option<String> {
+    /// Collects the names of all keyframes defined in this block.
+    pub(crate) fn collect_keyframes(&self, names: &mut Vec<String>) {
+        for i in self.content.iter() {
+            i.collect_keyframes(names);
+        }
+    }
+
+    fn selectors(&self, ctx: &mut StyleContext<'_>) -> Vec<String> {
         if self.condition.is_empty() {
-            return None;
+            return Vec::new();
         }
 
-        let mut cond = "".to_string();
+        let parents: Vec<String> = ctx.selectors().iter().map(|m| m.to_string()).collect();
 
-        for (index, sel) in self.condition.iter().enumerate() {
-            sel.write_style(&mut cond, ctx);
-            if index < self.condition.len() - 1 {
-                cond.push_str(", ");
+        // Not nested in any block, selectors are scoped with the class name.
+        if parents.is_empty() {
+            return self
+                .condition
+                .iter()
+                .map(|sel| {
+                    let mut s = "".to_string();
+                    sel.write_style(&mut s, ctx);
+                    s
+                })
+                .collect();
+        }
+
+        // A selector that refers to a parent selector list more than once, e.g.: `& + &`, matches
+        // any combination of the parent selectors, so the parent selector list is wrapped in
+        // `:is()` instead of being expanded.
+        let parent_list = format!(":is({})", parents.join(", "));
+
+        // Otherwise, a selector list nested in a selector list yields every combination of them.
+        let mut selectors = Vec::with_capacity(parents.len() * self.condition.len());
+        for (index, parent) in parents.iter().enumerate() {
+            for sel in self.condition.iter() {
+                let mut s = "".to_string();
+                if parents.len() > 1 && sel.nesting_count() > 1 {
+                    if index > 0 {
+                        continue;
+                    }
+                    sel.write_nested_style(&mut s, &parent_list, ctx);
+                } else if has_combinator(parent) && sel.has_nesting() && !sel.starts_with_nesting()
+                {
+                    // The parent selector must match the element of the current selector as a
+                    // whole, e.g.: `div&` nested in `.a .b` is `div:is(.a .b)`, not `div.a .b`.
+                    sel.write_nested_style(&mut s, &format!(":is({parent})"), ctx);
+                } else {
+                    sel.write_nested_style(&mut s, parent, ctx);
+                }
+                selectors.push(s);
             }
         }
 
-        Some(cond)
+        selectors
+    }
+}
+
+/// Returns `true` if a selector has a combinator that is not in brackets or a string.
+fn has_combinator(selector: &str) -> bool {
+    let mut depth = 0usize;
+    let mut quote = None;
+    let mut chars = selector.trim().chars();
+
+    while let Some(c) = chars.next() {
+        match (c, quote) {
+            ('\\', _) => {
+                chars.next();
+            }
+            (m, Some(q)) if m == q => quote = None,
+            (_, Some(_)) => {}
+            ('"' | '\'', None) => quote = Some(c),
+            ('(' | '[', None) => depth += 1,
+            (')' | ']', None) => depth = depth.saturating_sub(1),
+            (' ' | '\t' | '\n' | '>' | '+' | '~', None) if depth == 0 => return true,
+            _ => {}
+        }
     }
+
+    false
 }
 
 impl ToStyleStr for Block {
     fn write_style(&self, w: &mut String, ctx: &mut StyleContext<'_>) {
-        // TODO: nested block, which is not supported at the moment.
-        let cond_s = self.cond_str(ctx);
+        let selectors = self.selectors(ctx);
 
-        let mut block_ctx = ctx.with_block_condition(cond_s);
+        let mut block_ctx = ctx.with_block_condition(selectors);
 
         for attr in self.content.iter() {
             attr.write_style(w, &mut block_ctx);
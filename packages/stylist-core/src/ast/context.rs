@@ -1,6 +1,39 @@
 use std::borrow::Cow;
-use std::iter::empty;
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+use super::OutputOptions;
+
+/// Conditional group rules can be merged with an adjacent rule of the same condition without
+/// changing the meaning of the stylesheet.
+fn is_mergeable(cond: &str) -> bool {
+    [
+        "@media",
+        "@supports",
+        "@container",
+        "@layer",
+        "@document",
+        "@scope",
+    ]
+    .iter()
+    .any(|m| cond.starts_with(m))
+}
+
+/// Returns the content of a quoted string, e.g.: `spin` for `"spin"`.
+pub(crate) fn unquote(s: &str) -> Option<&str> {
+    ['"', '\'']
+        .iter()
+        .find_map(|&q| s.strip_prefix(q).and_then(|m| m.strip_suffix(q)))
+}
+
+/// The state of the output shared by all contexts writing the same stylesheet.
+#[derive(Debug, Default)]
+struct Output {
+    // The conditions that are currently open and the id of the context that opened them.
+    open: Vec<(String, usize)>,
+    next_id: usize,
+    // The offset of the semicolon after the last declaration.
+    last_semicolon: Option<usize>,
+}
 
 /// A context to faciliate [`ToStyleStr`](super::ToStyleStr).
 #[derive(Debug)]
@@ -9,51 +42,71 @@ pub struct StyleContext<'a> {
     parent_ctx: Option<&'a StyleContext<'a>>,
 
     rules: Vec<Cow<'a, str>>,
-    // selectors: Vec<Vec<Cow<'a, str>>>,
+    selectors: Vec<Cow<'a, str>>,
     selector: Option<Cow<'a, str>>,
 
-    is_open: AtomicBool,
+    keyframes: Arc<[String]>,
+
+    options: OutputOptions,
+
+    id: usize,
+    output: Arc<Mutex<Output>>,
 }
 
 impl<'a> StyleContext<'a> {
     /// Creates a new context.
     pub fn new(class_name: Option<&'a str>) -> Self {
+        Self::new_with_options(class_name, OutputOptions::default())
+    }
+
+    /// Creates a new context that writes with the given output options.
+    pub fn new_with_options(class_name: Option<&'a str>, options: OutputOptions) -> Self {
         Self {
             parent_ctx: None,
             class_name,
             rules: Vec::new(),
+            selectors: Vec::new(),
             selector: None,
 
-            is_open: AtomicBool::new(false),
+            keyframes: Arc::from([]),
+
+            options,
+
+            id: 0,
+            output: Arc::default(),
         }
     }
 
-    fn is_open(&self) -> bool {
-        self.is_open.load(Ordering::Relaxed)
-    }
+    fn child(
+        &'a self,
+        rules: Vec<Cow<'a, str>>,
+        selectors: Vec<Cow<'a, str>>,
+        selector: Option<Cow<'a, str>>,
+    ) -> Self {
+        let id = {
+            let mut output = self.output.lock().unwrap();
+            output.next_id += 1;
+            output.next_id
+        };
 
-    fn set_open(&self, value: bool) {
-        self.is_open.store(value, Ordering::Relaxed);
-    }
+        Self {
+            parent_ctx: Some(self),
+            class_name: self.class_name,
+            rules,
+            selectors,
+            selector,
 
-    // We close until we can find a parent that has nothing differs from current path.
-    fn close_until_common_parent(&self, w: &mut String) {
-        while let Some(m) = self.open_parent() {
-            if self.differ_conditions().next().is_none() {
-                break;
-            }
-            m.finish(w);
+            keyframes: self.keyframes.clone(),
+
+            options: self.options,
+
+            id,
+            output: self.output.clone(),
         }
     }
 
-    fn open_parent(&self) -> Option<&StyleContext<'a>> {
-        self.parent_ctx.and_then(|m| {
-            if m.is_open() {
-                Some(m)
-            } else {
-                m.open_parent()
-            }
-        })
+    fn is_self_or_ancestor(&self, id: usize) -> bool {
+        self.id == id || self.parent_ctx.is_some_and(|m| m.is_self_or_ancestor(id))
     }
 
     fn conditions(&self) -> impl Iterator<Item = &str> {
@@ -63,62 +116,136 @@ impl<'a> StyleContext<'a> {
             .map(|m| m.as_ref())
     }
 
-    fn common_conditions(&self) -> impl Iterator<Item = &str> {
-        self.open_parent()
-            .map(|m| Box::new(m.conditions()) as Box<dyn Iterator<Item = &str>>)
-            .unwrap_or_else(|| Box::new(empty()))
-            .zip(self.conditions())
-            .filter_map(|(m1, m2)| if m1 == m2 { Some(m1) } else { None })
-    }
+    /// The number of open conditions that this context can write into.
+    ///
+    /// Conditions opened by this context or its parents are always shared. Other conditions are
+    /// only shared if they are conditional group rules that enclose the innermost condition.
+    fn shared_len(&self, open: &[(String, usize)]) -> usize {
+        let conditions: Vec<&str> = self.conditions().collect();
+        let innermost = conditions.len().saturating_sub(1);
 
-    /// Calculate the layers that current context differs from the parent context
-    fn unique_conditions(&self) -> impl Iterator<Item = &str> {
-        self.conditions().skip(self.common_conditions().count())
+        open.iter()
+            .zip(conditions)
+            .enumerate()
+            .take_while(|(index, ((cond, owner), m))| {
+                cond == m
+                    && (self.is_self_or_ancestor(*owner) || (*index < innermost && is_mergeable(m)))
+            })
+            .count()
     }
 
-    /// Calculate the layers that parent context differs from current context
-    fn differ_conditions(&self) -> impl Iterator<Item = &str> {
-        self.open_parent()
-            .map(|m| Box::new(m.conditions()) as Box<dyn Iterator<Item = &str>>)
-            .unwrap_or_else(|| Box::new(empty()))
-            .skip(self.common_conditions().count())
+    fn write_padding_impl(&self, w: &mut String, no: usize) {
+        if !self.options.minify {
+            w.extend(std::iter::repeat_n(' ', no * self.options.indent));
+        }
     }
 
-    fn write_padding_impl(&self, w: &mut String, no: usize) {
-        for _ in 0..no {
-            w.push_str("    ");
+    fn write_newline(&self, w: &mut String) {
+        if !self.options.minify {
+            w.push('\n');
         }
     }
 
-    fn write_min_padding(&self, w: &mut String) {
-        self.write_padding_impl(w, self.common_conditions().count())
+    /// Writes `}` until only `len` conditions are open.
+    fn close_to(&self, w: &mut String, output: &mut Output, len: usize) {
+        while output.open.len() > len {
+            output.open.pop();
+
+            self.remove_trailing_semicolon(w, output);
+            self.write_padding_impl(w, output.open.len());
+            w.push('}');
+            self.write_newline(w);
+        }
     }
 
     /// Writes the condition and `{`.
+    ///
+    /// Conditions that are already open are reused.
     pub fn start(&self, w: &mut String) {
-        if !self.is_open() {
-            self.close_until_common_parent(w);
-
-            for (index, cond) in self.unique_conditions().enumerate() {
-                self.write_min_padding(w);
-                self.write_padding_impl(w, index);
-                w.push_str(cond);
-                w.push_str(" {\n");
+        let mut output = self.output.lock().unwrap();
+
+        let shared_len = self.shared_len(&output.open);
+        self.close_to(w, &mut output, shared_len);
+
+        for cond in self.conditions().skip(shared_len) {
+            self.write_padding_impl(w, output.open.len());
+            w.push_str(cond);
+            if !self.options.minify {
+                w.push(' ');
             }
+            w.push('{');
+            self.write_newline(w);
+
+            output.open.push((cond.to_string(), self.id));
         }
-        self.set_open(true);
     }
 
     /// Writes `}`.
+    ///
+    /// Only the innermost condition of this context is closed, enclosing conditional group rules
+    /// are kept open so following output with the same conditions is merged into them. Finishing
+    /// the root context closes all conditions.
     pub fn finish(&self, w: &mut String) {
-        if self.is_open() {
-            for i in (0..self.unique_conditions().count()).rev() {
-                self.write_min_padding(w);
-                self.write_padding_impl(w, i);
-                w.push_str("}\n");
+        let mut output = self.output.lock().unwrap();
+
+        match self.conditions().count() {
+            0 => self.close_to(w, &mut output, 0),
+            len => {
+                if output.open.get(len - 1).is_some_and(|m| m.1 == self.id) {
+                    self.close_to(w, &mut output, len - 1);
+                }
+            }
+        }
+    }
+
+    /// Writes an at-rule statement that has no block, e.g.: `@layer base, components;`.
+    ///
+    /// Statements are never wrapped in a selector.
+    pub fn write_statement(&self, w: &mut String, statement: &str) {
+        if self.selector.is_none() {
+            self.start(w);
+            self.write_padding(w);
+            w.push_str(statement);
+            w.push(';');
+            self.write_newline(w);
+
+            return;
+        }
+
+        // Move the statement out of the selector.
+        let ctx = self.child(self.rules.clone(), Vec::new(), None);
+
+        ctx.write_statement(w, statement);
+        ctx.finish(w);
+    }
+
+    /// The options used to write the stylesheet.
+    pub fn options(&self) -> &OutputOptions {
+        &self.options
+    }
+
+    /// Writes the `;` that ends a declaration.
+    ///
+    /// The semicolon is removed again if the block ends right after it and trailing semicolons
+    /// are disabled.
+    pub fn write_declaration_end(&self, w: &mut String) {
+        self.output.lock().unwrap().last_semicolon = Some(w.len());
+        w.push(';');
+        self.write_newline(w);
+    }
+
+    fn remove_trailing_semicolon(&self, w: &mut String, output: &mut Output) {
+        if self.options.trailing_semicolon {
+            return;
+        }
+
+        let newline_len = if self.options.minify { 0 } else { 1 };
+        if let Some(pos) = output.last_semicolon {
+            if pos + 1 + newline_len == w.len() {
+                w.remove(pos);
+                output.last_semicolon = None;
             }
         }
-        self.set_open(false);
     }
 
     /// Write the space that matches the indentation level of current context.
@@ -126,30 +253,68 @@ impl<'a> StyleContext<'a> {
         self.write_padding_impl(w, self.conditions().count());
     }
 
+    /// Sets the names of keyframes defined in the stylesheet being written.
+    pub(crate) fn set_keyframes(&mut self, names: Vec<String>) {
+        self.keyframes = names.into();
+    }
+
+    /// Returns the name of a keyframes rule as it should be written.
+    ///
+    /// Keyframes defined in a scoped stylesheet are prefixed with the class name so they do not
+    /// clash with keyframes of other stylesheets. Names of keyframes that are not defined in the
+    /// stylesheet are returned unchanged.
+    ///
+    /// Names written as strings, e.g.: `"spin"`, are renamed inside the quotes.
+    pub fn keyframes_name<'b>(&self, name: &'b str) -> Cow<'b, str> {
+        let (quote, unquoted) = match unquote(name) {
+            Some(m) => (&name[..1], m),
+            None => ("", name),
+        };
+
+        match self.class_name {
+            Some(m) if self.keyframes.iter().any(|k| k == unquoted) => {
+                format!("{quote}{m}-{unquoted}{quote}").into()
+            }
+            _ => name.into(),
+        }
+    }
+
+    /// The resolved selectors of the closest enclosing block.
+    ///
+    /// This is empty if the current context is not nested in any block.
+    pub fn selectors(&self) -> &[Cow<'a, str>] {
+        &self.selectors
+    }
+
     /// Creates a child context for a block.
-    pub fn with_block_condition<S>(&'a self, cond: Option<S>) -> Self
+    ///
+    /// The selectors passed to this method should already be resolved against the selectors of
+    /// the parent block. If no selector is provided, the selectors of the parent context will be
+    /// used.
+    pub fn with_block_condition<I, S>(&'a self, selectors: I) -> Self
     where
+        I: IntoIterator<Item = S>,
         S: Into<Cow<'a, str>>,
     {
-        // This logic is find as long as there's no
-        let selector = cond
-            // Use condition provided
-            .map(|m| m.into())
-            // Use the selector of parent context
-            .or_else(|| self.selector.clone())
-            // Use class name of scope context
-            .or_else(|| self.class_name.map(|m| format!(".{m}").into()))
-            // Use html
-            .or_else(|| Some(":root".into()));
-
-        Self {
-            parent_ctx: Some(self),
-            class_name: self.class_name,
-            rules: self.rules.clone(),
-            selector,
+        let mut selectors: Vec<Cow<'a, str>> = selectors.into_iter().map(|m| m.into()).collect();
 
-            is_open: AtomicBool::new(false),
+        if selectors.is_empty() {
+            selectors = if !self.selectors.is_empty() {
+                // Use the selectors of parent context
+                self.selectors.clone()
+            } else if let Some(m) = self.class_name {
+                // Use class name of scope context
+                vec![format!(".{m}").into()]
+            } else {
+                // Use html
+                vec![":root".into()]
+            };
         }
+
+        let separator = if self.options.minify { "," } else { ", " };
+        let selector = Some(selectors.join(separator).into());
+
+        self.child(self.rules.clone(), selectors, selector)
     }
 
     /// Creates a child context for a rule.
@@ -157,13 +322,14 @@ impl<'a> StyleContext<'a> {
         let mut rules = self.rules.clone();
         rules.push(cond.into());
 
-        Self {
-            parent_ctx: Some(self),
-            class_name: self.class_name,
-            rules,
-            selector: self.selector.clone(),
+        self.child(rules, self.selectors.clone(), self.selector.clone())
+    }
 
-            is_open: AtomicBool::new(false),
-        }
+    /// Creates a child context for a rule that is never scoped by a selector, e.g.: `@font-face`.
+    pub fn with_unscoped_rule_condition<S: Into<Cow<'a, str>>>(&'a self, cond: S) -> Self {
+        let mut rules = self.rules.clone();
+        rules.push(cond.into());
+
+        self.child(rules, Vec::new(), None)
     }
 }
@@ -2,25 +2,35 @@
 
 mod block;
 mod context;
+mod output_options;
 mod rule;
 mod rule_block_content;
 mod scope_content;
 mod selector;
 mod sheet;
+#[cfg(feature = "parser")]
+mod sheet_cache;
+mod span;
 mod str_frag;
 mod style_attr;
 mod to_style_str;
+mod value;
 
 pub use context::StyleContext;
+pub use output_options::OutputOptions;
 
 pub use block::Block;
 pub use rule::Rule;
 pub use rule_block_content::RuleBlockContent;
 pub use scope_content::ScopeContent;
-pub use selector::Selector;
+pub use selector::{Combinator, CompoundSelector, PseudoArgument, Selector, SimpleSelector};
 pub use sheet::Sheet;
+#[cfg(feature = "parser")]
+pub use sheet_cache::{SheetCache, SheetCacheStats};
+pub use span::Span;
 pub use style_attr::StyleAttribute;
 pub use to_style_str::ToStyleStr;
+pub use value::ComponentValue;
 
 pub use str_frag::StringFragment;
 
@@ -30,6 +40,14 @@ mod tests {
     use crate::*;
     use std::borrow::Cow;
 
+    fn sel(selectors: Vec<SimpleSelector>) -> Selector {
+        vec![CompoundSelector::from(selectors)].into()
+    }
+
+    fn class(s: &'static str) -> SimpleSelector {
+        SimpleSelector::Class(vec![s.into()].into())
+    }
+
     #[test]
     fn test_scope_building_without_condition() -> Result<()> {
         let test_block = Sheet::from(vec![
@@ -38,18 +56,26 @@ mod tests {
                 content: vec![StyleAttribute {
                     key: "width".into(),
                     value: vec!["100vw".into()].into(),
+                    important: false,
+                    component_values: None,
+                    span: None,
                 }
                 .into()]
                 .into(),
+                span: None,
             }),
             ScopeContent::Block(Block {
-                condition: vec![vec![".inner".into()].into()].into(),
+                condition: vec![sel(vec![class("inner")])].into(),
                 content: vec![StyleAttribute {
                     key: "background-color".into(),
                     value: vec!["red".into()].into(),
+                    important: false,
+                    component_values: None,
+                    span: None,
                 }
                 .into()]
                 .into(),
+                span: None,
             }),
             ScopeContent::Rule(Rule {
                 condition: vec!["@keyframes move".into()].into(),
@@ -60,8 +86,12 @@ mod tests {
                             content: vec![RuleBlockContent::StyleAttr(StyleAttribute {
                                 key: "width".into(),
                                 value: vec!["100px".into()].into(),
+                                important: false,
+                                component_values: None,
+                                span: None,
                             })]
                             .into(),
+                            span: None,
                         }
                         .into(),
                     ),
@@ -71,13 +101,18 @@ mod tests {
                             content: vec![RuleBlockContent::StyleAttr(StyleAttribute {
                                 key: "width".into(),
                                 value: vec!["200px".into()].into(),
+                                important: false,
+                                component_values: None,
+                                span: None,
                             })]
                             .into(),
+                            span: None,
                         }
                         .into(),
                     ),
                 ]
                 .into(),
+                span: None,
             }),
         ]);
         assert_eq!(
@@ -88,7 +123,7 @@ mod tests {
 .test .inner {
     background-color: red;
 }
-@keyframes move {
+@keyframes test-move {
     from {
         width: 100px;
     }
@@ -113,21 +148,29 @@ mod tests {
                         content: vec![StyleAttribute {
                             key: "width".into(),
                             value: vec!["100vw".into()].into(),
+                            important: false,
+                            component_values: None,
+                            span: None,
                         }
                         .into()]
                         .into(),
+                        span: None,
                     }
                     .into(),
                 ),
                 RuleBlockContent::Block(
                     Block {
-                        condition: vec![vec![".inner".into()].into()].into(),
+                        condition: vec![sel(vec![class("inner")])].into(),
                         content: vec![StyleAttribute {
                             key: "background-color".into(),
                             value: vec!["red".into()].into(),
+                            important: false,
+                            component_values: None,
+                            span: None,
                         }
                         .into()]
                         .into(),
+                        span: None,
                     }
                     .into(),
                 ),
@@ -141,8 +184,12 @@ mod tests {
                                     content: vec![RuleBlockContent::StyleAttr(StyleAttribute {
                                         key: "width".into(),
                                         value: vec!["100px".into()].into(),
+                                        important: false,
+                                        component_values: None,
+                                        span: None,
                                     })]
                                     .into(),
+                                    span: None,
                                 }
                                 .into(),
                             ),
@@ -152,18 +199,24 @@ mod tests {
                                     content: vec![RuleBlockContent::StyleAttr(StyleAttribute {
                                         key: "width".into(),
                                         value: vec!["200px".into()].into(),
+                                        important: false,
+                                        component_values: None,
+                                        span: None,
                                     })]
                                     .into(),
+                                    span: None,
                                 }
                                 .into(),
                             ),
                         ]
                         .into(),
+                        span: None,
                     }
                     .into(),
                 ),
             ]
             .into(),
+            span: None,
         })]);
         assert_eq!(
             test_block.to_style_str(Some("test")),
@@ -171,14 +224,10 @@ mod tests {
     .test {
         width: 100vw;
     }
-}
-@media only screen and (min-width: 1000px) {
     .test .inner {
         background-color: red;
     }
-}
-@media only screen and (min-width: 1000px) {
-    @keyframes move {
+    @keyframes test-move {
         from {
             width: 100px;
         }
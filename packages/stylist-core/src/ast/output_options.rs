@@ -0,0 +1,98 @@
+/// Options that control how a stylesheet is written by [`ToStyleStr`](super::ToStyleStr).
+///
+/// # Examples
+///
+/// ```
+/// use stylist_core::ast::{OutputOptions, Sheet, ToStyleStr};
+///
+/// let sheet: Sheet = "color: red; .a { width: 1px; }".parse()?;
+///
+/// assert_eq!(
+///     sheet.to_style_str_with_options(Some("x"), OutputOptions::minified()),
+///     ".x{color:red}.x .a{width:1px}"
+/// );
+/// # Ok::<(), stylist_core::Error>(())
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct OutputOptions {
+    /// Omits all optional whitespace.
+    pub minify: bool,
+    /// The number of spaces per indentation level, ignored when minified.
+    pub indent: usize,
+    /// Writes a semicolon after the last declaration of a block.
+    pub trailing_semicolon: bool,
+}
+
+impl OutputOptions {
+    /// Human readable output with newlines and an indentation of four spaces.
+    pub const fn pretty() -> Self {
+        Self {
+            minify: false,
+            indent: 4,
+            trailing_semicolon: true,
+        }
+    }
+
+    /// The smallest possible output.
+    pub const fn minified() -> Self {
+        Self {
+            minify: true,
+            indent: 0,
+            trailing_semicolon: false,
+        }
+    }
+}
+
+impl Default for OutputOptions {
+    fn default() -> Self {
+        Self::pretty()
+    }
+}
+
+#[cfg(all(test, feature = "parser"))]
+mod tests {
+    use super::*;
+    use crate::ast::{Sheet, ToStyleStr};
+
+    #[test]
+    fn test_output_options() -> crate::Result<()> {
+        let sheet: Sheet = r#"
+            color: red;
+            a, b { width: 1px; height: 2px !important; }
+            @media print { color: black; }
+            @layer base, components;
+        "#
+        .parse()?;
+
+        assert_eq!(
+            sheet.to_style_str_with_options(Some("x"), OutputOptions::minified()),
+            ".x{color:red}.x a,.x b{width:1px;height:2px!important}\
+             @media print{.x{color:black}}@layer base, components;"
+        );
+
+        let options = OutputOptions {
+            indent: 2,
+            trailing_semicolon: false,
+            ..Default::default()
+        };
+        assert_eq!(
+            sheet.to_style_str_with_options(Some("x"), options),
+            r#".x {
+  color: red
+}
+.x a, .x b {
+  width: 1px;
+  height: 2px !important
+}
+@media print {
+  .x {
+    color: black
+  }
+}
+@layer base, components;
+"#
+        );
+
+        Ok(())
+    }
+}
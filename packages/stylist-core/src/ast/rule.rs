@@ -1,8 +1,10 @@
 use std::borrow::Cow;
+use std::hash::{Hash, Hasher};
 
 use serde::{Deserialize, Serialize};
 
-use super::{RuleBlockContent, StringFragment, StyleContext, ToStyleStr};
+use super::context::unquote;
+use super::{RuleBlockContent, Span, StringFragment, StyleContext, ToStyleStr};
 
 /// An At-Rule can contain both other blocks and in some cases more At-Rules.
 ///
@@ -17,10 +19,73 @@ use super::{RuleBlockContent, StringFragment, StyleContext, ToStyleStr};
 ///     }
 /// }
 /// ```
-#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+///
+/// A `@layer` rule without any content is written as a statement:
+/// ```css
+/// @layer base, components;
+/// ```
+///
+/// Some At-Rules only contain style attributes:
+/// ```css
+/// @font-face {
+///     font-family: "Open Sans";
+///     src: url("/fonts/OpenSans-Regular.woff2") format("woff2");
+/// }
+/// ```
+#[derive(Debug, Clone, Eq, Serialize, Deserialize)]
 pub struct Rule {
     pub condition: Cow<'static, [StringFragment]>,
     pub content: Cow<'static, [RuleBlockContent]>,
+    /// The position of this rule in the source, if it is parsed at runtime.
+    #[serde(skip)]
+    pub span: Option<Span>,
+}
+
+// Spans are only used for diagnostics and are not compared.
+impl PartialEq for Rule {
+    fn eq(&self, other: &Self) -> bool {
+        self.condition == other.condition && self.content == other.content
+    }
+}
+
+impl Hash for Rule {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.condition.hash(state);
+        self.content.hash(state);
+    }
+}
+
+/// At-keywords of rules that define keyframes.
+const KEYFRAMES_KEYWORDS: &[&str] = &["@keyframes", "@-webkit-keyframes"];
+
+/// Splits the condition of a keyframes rule into its at-keyword and its name.
+fn split_keyframes(cond: &str) -> Option<(&'static str, &str)> {
+    KEYFRAMES_KEYWORDS.iter().find_map(|&kw| {
+        cond.strip_prefix(kw)
+            .filter(|m| m.starts_with(char::is_whitespace))
+            .map(|m| (kw, m.trim()))
+    })
+}
+
+impl Rule {
+    /// Returns the name of the keyframes if this is a `@keyframes` rule.
+    fn keyframes_name(&self) -> Option<String> {
+        let cond: String = self.condition.iter().map(|m| m.inner.as_ref()).collect();
+
+        split_keyframes(&cond).map(|(_, name)| unquote(name).unwrap_or(name).to_string())
+    }
+
+    /// Collects the names of all keyframes defined in this rule.
+    pub(crate) fn collect_keyframes(&self, names: &mut Vec<String>) {
+        if let Some(m) = self.keyframes_name() {
+            names.push(m);
+            return;
+        }
+
+        for i in self.content.iter() {
+            i.collect_keyframes(names);
+        }
+    }
 }
 
 impl ToStyleStr for Rule {
@@ -30,11 +95,28 @@ impl ToStyleStr for Rule {
             frag.write_style(&mut cond, ctx);
         }
 
-        let mut rule_ctx = ctx.with_rule_condition(&cond);
+        // Keyframes in a scoped stylesheet are renamed to avoid clashing with other stylesheets.
+        if let Some((kw, name)) = split_keyframes(&cond) {
+            cond = format!("{kw} {}", ctx.keyframes_name(name));
+        }
+
+        // A layer without content is a statement that declares the order of layers.
+        if cond.starts_with("@layer") && self.content.is_empty() {
+            ctx.write_statement(w, &cond);
+            return;
+        }
+
+        // keyframes and font faces should always be printed as they contain a global name.
+        // They should also never be wrapped in a selector.
+        let is_global = split_keyframes(&cond).is_some() || cond.starts_with("@font-face");
+
+        let mut rule_ctx = if is_global {
+            ctx.with_unscoped_rule_condition(&cond)
+        } else {
+            ctx.with_rule_condition(&cond)
+        };
 
-        // keyframes should always be printed as they contain a global name.
-        let always_print = cond.starts_with("@keyframes");
-        if always_print {
+        if is_global {
             rule_ctx.start(w);
         }
 
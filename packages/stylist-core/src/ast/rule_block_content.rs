@@ -1,6 +1,6 @@
 use serde::{Deserialize, Serialize};
 
-use super::{Block, Rule, StyleAttribute, StyleContext, ToStyleStr};
+use super::{Block, Rule, ScopeContent, StyleAttribute, StyleContext, ToStyleStr};
 use crate::bow::Bow;
 
 /// The content of a [`Rule`] or a [`Block`]
@@ -17,6 +17,25 @@ impl From<StyleAttribute> for RuleBlockContent {
     }
 }
 
+impl RuleBlockContent {
+    pub(crate) fn collect_keyframes(&self, names: &mut Vec<String>) {
+        match self {
+            Self::StyleAttr(_) => {}
+            Self::Rule(ref m) => m.collect_keyframes(names),
+            Self::Block(ref m) => m.collect_keyframes(names),
+        }
+    }
+}
+
+impl From<ScopeContent> for RuleBlockContent {
+    fn from(s: ScopeContent) -> Self {
+        match s {
+            ScopeContent::Block(m) => Self::Block(Bow::Boxed(Box::new(m))),
+            ScopeContent::Rule(m) => Self::Rule(Bow::Boxed(Box::new(m))),
+        }
+    }
+}
+
 impl ToStyleStr for RuleBlockContent {
     fn write_style(&self, w: &mut String, ctx: &mut StyleContext<'_>) {
         match self {
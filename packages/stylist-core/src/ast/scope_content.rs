@@ -28,6 +28,15 @@ pub enum ScopeContent {
     Rule(Rule),
 }
 
+impl ScopeContent {
+    pub(crate) fn collect_keyframes(&self, names: &mut Vec<String>) {
+        match self {
+            ScopeContent::Block(ref b) => b.collect_keyframes(names),
+            ScopeContent::Rule(ref r) => r.collect_keyframes(names),
+        }
+    }
+}
+
 impl ToStyleStr for ScopeContent {
     fn write_style(&self, w: &mut String, ctx: &mut StyleContext<'_>) {
         match self {
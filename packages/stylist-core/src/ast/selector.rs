@@ -1,62 +1,432 @@
 use std::borrow::Cow;
+use std::hash::{Hash, Hasher};
 
 use serde::{Deserialize, Serialize};
 
-use super::{StringFragment, StyleContext, ToStyleStr};
+use super::{Span, StringFragment, StyleContext, ToStyleStr};
 
-/// A CSS Selector.
+/// A combinator that joins a [`CompoundSelector`] to the one before it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Combinator {
+    /// ` `
+    Descendant,
+    /// `>`
+    Child,
+    /// `+`
+    NextSibling,
+    /// `~`
+    SubsequentSibling,
+}
+
+impl Combinator {
+    /// The character that represents this combinator.
+    pub fn as_char(&self) -> char {
+        match self {
+            Self::Descendant => ' ',
+            Self::Child => '>',
+            Self::NextSibling => '+',
+            Self::SubsequentSibling => '~',
+        }
+    }
+
+    fn write_style(&self, w: &mut String, is_first: bool) {
+        match (self, is_first) {
+            (Self::Descendant, true) => {}
+            (Self::Descendant, false) => w.push(' '),
+            (m, true) => {
+                w.push(m.as_char());
+                w.push(' ');
+            }
+            (m, false) => {
+                w.push(' ');
+                w.push(m.as_char());
+                w.push(' ');
+            }
+        }
+    }
+}
+
+/// The argument of a pseudo-class or a pseudo-element.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum PseudoArgument {
+    /// A selector list, e.g.: `:not(&.active, .disabled)`.
+    Selectors(Cow<'static, [Selector]>),
+    /// Any other argument, e.g.: `:nth-child(2n + 1)`.
+    Raw(Cow<'static, [StringFragment]>),
+}
+
+impl PseudoArgument {
+    /// Pseudo-classes and pseudo-elements that accept a selector list as their argument.
+    pub const SELECTOR_FUNCTIONS: &'static [&'static str] = &[
+        "is",
+        "not",
+        "where",
+        "has",
+        "host",
+        "host-context",
+        "slotted",
+    ];
+}
+
+/// A simple selector, which is a part of a [`CompoundSelector`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum SimpleSelector {
+    /// The current selector, `&`.
+    Nesting,
+    /// A type selector or the universal selector, e.g.: `div` or `*`.
+    Type(Cow<'static, [StringFragment]>),
+    /// A class selector without the leading `.`, e.g.: `my-class`.
+    Class(Cow<'static, [StringFragment]>),
+    /// An id selector without the leading `#`, e.g.: `some-id`.
+    Id(Cow<'static, [StringFragment]>),
+    /// An attribute selector with the content between the brackets, e.g.: `attr="val"`.
+    Attribute(Cow<'static, [StringFragment]>),
+    /// A pseudo-class without the leading `:`, e.g.: `hover` or `not(.active)`.
+    PseudoClass {
+        name: Cow<'static, [StringFragment]>,
+        argument: Option<PseudoArgument>,
+    },
+    /// A pseudo-element without the leading `::`, e.g.: `before`.
+    PseudoElement {
+        name: Cow<'static, [StringFragment]>,
+        argument: Option<PseudoArgument>,
+    },
+    /// Fragments that are written as is, e.g.: an interpolated selector.
+    Raw(Cow<'static, [StringFragment]>),
+}
+
+/// A sequence of simple selectors that are not separated by a combinator.
 ///
 /// E.g.:
 /// ```css
 /// div[attr="val"].my-class#some-id
 /// ```
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct CompoundSelector {
+    /// The combinator that joins this compound selector to the previous one.
+    ///
+    /// If this is set on the first compound selector, the selector is relative to the selector it
+    /// is applied to, e.g.: `> a`.
+    pub combinator: Option<Combinator>,
+    pub selectors: Cow<'static, [SimpleSelector]>,
+}
+
+/// A CSS Selector.
+///
+/// E.g.:
+/// ```css
+/// &.active > div[attr="val"]:hover
+/// ```
+#[derive(Debug, Clone, Eq, Serialize, Deserialize)]
 pub struct Selector {
-    pub fragments: Cow<'static, [StringFragment]>,
+    pub compounds: Cow<'static, [CompoundSelector]>,
+    /// The position of this selector in the source, if it is parsed at runtime.
+    #[serde(skip)]
+    pub span: Option<Span>,
 }
 
-impl ToStyleStr for Selector {
-    fn write_style(&self, w: &mut String, ctx: &mut StyleContext<'_>) {
-        let mut joined_s = "".to_string();
+// Selectors with the same parts are equal wherever they are parsed from.
+impl PartialEq for Selector {
+    fn eq(&self, other: &Self) -> bool {
+        self.compounds == other.compounds
+    }
+}
+
+impl Hash for Selector {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.compounds.hash(state);
+    }
+}
+
+/// Replacements of the current selector (`&`) and the root pseudo class (`:root`).
+struct Substitution<'a> {
+    nesting: &'a str,
+    root: Option<&'a str>,
+}
+
+fn write_fragments(w: &mut String, frags: &[StringFragment], ctx: &mut StyleContext<'_>) {
+    for frag in frags.iter() {
+        frag.write_style(w, ctx);
+    }
+}
+
+fn is_root(name: &[StringFragment]) -> bool {
+    matches!(name, [m] if m.inner == "root")
+}
+
+// Raw selectors are usually interpolated at runtime, so the current selector and the root pseudo
+// class can only be found by looking at their text.
+fn raw_contains(frags: &[StringFragment], pat: &str) -> bool {
+    frags.iter().any(|m| m.inner.contains(pat))
+}
+
+fn write_raw(
+    w: &mut String,
+    frags: &[StringFragment],
+    subst: &Substitution<'_>,
+    ctx: &mut StyleContext<'_>,
+) {
+    let mut raw = String::new();
+    write_fragments(&mut raw, frags, ctx);
+
+    let mut raw = raw.replace('&', subst.nesting);
+    if let Some(root) = subst.root {
+        raw = raw.replace(":root", root);
+    }
+
+    w.push_str(&raw);
+}
+
+impl PseudoArgument {
+    fn write_style(&self, w: &mut String, subst: &Substitution<'_>, ctx: &mut StyleContext<'_>) {
+        w.push('(');
+        match self {
+            Self::Selectors(ref m) => {
+                for (index, sel) in m.iter().enumerate() {
+                    if index > 0 {
+                        w.push_str(", ");
+                    }
+                    sel.write_parts(w, subst, ctx);
+                }
+            }
+            Self::Raw(ref m) => write_fragments(w, m, ctx),
+        }
+        w.push(')');
+    }
+
+    fn selectors(&self) -> &[Selector] {
+        match self {
+            Self::Selectors(ref m) => m,
+            Self::Raw(_) => &[],
+        }
+    }
+}
+
+impl SimpleSelector {
+    fn write_style(&self, w: &mut String, subst: &Substitution<'_>, ctx: &mut StyleContext<'_>) {
+        match self {
+            Self::Nesting => w.push_str(subst.nesting),
+            Self::Type(ref m) => write_fragments(w, m, ctx),
+            Self::Raw(ref m) => write_raw(w, m, subst, ctx),
+            Self::Class(ref m) => {
+                w.push('.');
+                write_fragments(w, m, ctx);
+            }
+            Self::Id(ref m) => {
+                w.push('#');
+                write_fragments(w, m, ctx);
+            }
+            Self::Attribute(ref m) => {
+                w.push('[');
+                write_fragments(w, m, ctx);
+                w.push(']');
+            }
+            Self::PseudoClass {
+                ref name,
+                ref argument,
+            } => match subst.root {
+                // Root pseudo class refers to the scoped element.
+                Some(root) if argument.is_none() && is_root(name) => w.push_str(root),
+                _ => {
+                    w.push(':');
+                    write_fragments(w, name, ctx);
+                    if let Some(ref m) = argument {
+                        m.write_style(w, subst, ctx);
+                    }
+                }
+            },
+            Self::PseudoElement {
+                ref name,
+                ref argument,
+            } => {
+                w.push_str("::");
+                write_fragments(w, name, ctx);
+                if let Some(ref m) = argument {
+                    m.write_style(w, subst, ctx);
+                }
+            }
+        }
+    }
+
+    fn argument(&self) -> Option<&PseudoArgument> {
+        match self {
+            Self::PseudoClass { ref argument, .. } | Self::PseudoElement { ref argument, .. } => {
+                argument.as_ref()
+            }
+            _ => None,
+        }
+    }
+
+    fn is_pseudo(&self) -> bool {
+        matches!(self, Self::PseudoClass { .. } | Self::PseudoElement { .. })
+    }
+}
+
+impl Selector {
+    fn simple_selectors(&self) -> impl Iterator<Item = &SimpleSelector> {
+        self.compounds.iter().flat_map(|m| m.selectors.iter())
+    }
+
+    /// Returns `true` if the selector contains a current selector (`&`).
+    pub fn has_nesting(&self) -> bool {
+        self.simple_selectors().any(|m| match m {
+            SimpleSelector::Nesting => true,
+            SimpleSelector::Raw(ref m) => raw_contains(m, "&"),
+            m => m
+                .argument()
+                .map(|m| m.selectors().iter().any(|m| m.has_nesting()))
+                .unwrap_or(false),
+        })
+    }
+
+    /// Returns the number of current selectors (`&`) in the selector.
+    pub(crate) fn nesting_count(&self) -> usize {
+        self.simple_selectors()
+            .map(|m| match m {
+                SimpleSelector::Nesting => 1,
+                SimpleSelector::Raw(ref m) => m.iter().map(|m| m.inner.matches('&').count()).sum(),
+                m => m
+                    .argument()
+                    .map(|m| m.selectors().iter().map(|m| m.nesting_count()).sum())
+                    .unwrap_or(0),
+            })
+            .sum()
+    }
+
+    /// Returns `true` if the selector has a single current selector (`&`), which starts the
+    /// selector, e.g.: `&:hover > a`.
+    pub(crate) fn starts_with_nesting(&self) -> bool {
+        let starts = match self.compounds.first() {
+            Some(m) if m.combinator.is_none() => match m.selectors.first() {
+                Some(SimpleSelector::Nesting) => true,
+                Some(SimpleSelector::Raw(ref m)) => m
+                    .first()
+                    .map(|m| m.inner.trim_start().starts_with('&'))
+                    .unwrap_or(false),
+                _ => false,
+            },
+            _ => false,
+        };
+
+        starts && self.nesting_count() == 1
+    }
+
+    /// Returns `true` if the selector contains a root pseudo class (`:root`).
+    pub fn has_root(&self) -> bool {
+        self.simple_selectors().any(|m| match m {
+            SimpleSelector::PseudoClass {
+                ref name,
+                argument: None,
+            } => is_root(name),
+            SimpleSelector::Raw(ref m) => raw_contains(m, ":root"),
+            m => m
+                .argument()
+                .map(|m| m.selectors().iter().any(|m| m.has_root()))
+                .unwrap_or(false),
+        })
+    }
+
+    /// Returns `true` if the selector starts with a pseudo-class or a pseudo-element.
+    fn starts_with_pseudo(&self) -> bool {
+        match self.compounds.first() {
+            Some(m) => {
+                m.combinator.is_none() && m.selectors.first().map(|m| m.is_pseudo()) == Some(true)
+            }
+            None => false,
+        }
+    }
+
+    fn write_parts(&self, w: &mut String, subst: &Substitution<'_>, ctx: &mut StyleContext<'_>) {
+        for (index, compound) in self.compounds.iter().enumerate() {
+            if let Some(ref m) = compound.combinator {
+                m.write_style(w, index == 0);
+            }
 
-        for frag in self.fragments.iter() {
-            frag.write_style(&mut joined_s, ctx);
+            for sel in compound.selectors.iter() {
+                sel.write_style(w, subst, ctx);
+            }
         }
+    }
+
+    /// Writes the selector as a nested selector of a parent selector.
+    ///
+    /// Current selector (`&`) is substituted with the parent selector. If the selector does not
+    /// contain a current selector, it will be treated as a relative selector of the parent
+    /// selector, like the CSS Nesting specification does.
+    pub(crate) fn write_nested_style(
+        &self,
+        w: &mut String,
+        parent: &str,
+        ctx: &mut StyleContext<'_>,
+    ) {
+        // Root pseudo class always refers to the scoped element, regardless of nesting.
+        let root = ctx.class_name.map(|m| format!(".{m}"));
+        let subst = Substitution {
+            nesting: parent,
+            root: root.as_deref(),
+        };
+
+        if !self.has_nesting() {
+            w.push_str(parent);
 
-        if let Some(ref m) = ctx.class_name {
+            // Same as the top level, pseudo-classes are applied to the parent element.
+            if !self.starts_with_pseudo() {
+                w.push(' ');
+            }
+        }
+
+        self.write_parts(w, &subst, ctx);
+    }
+}
+
+impl ToStyleStr for Selector {
+    fn write_style(&self, w: &mut String, ctx: &mut StyleContext<'_>) {
+        if let Some(m) = ctx.class_name {
             let scoped_class = format!(".{m}");
-            // If contains current selector or root pseudo class, replace them with class name.
-            if joined_s.contains('&') || joined_s.contains(":root") {
-                w.push_str(
-                    &joined_s
-                        .replace('&', scoped_class.as_str())
-                        .replace(":root", scoped_class.as_str()),
-                );
-            } else {
+            let subst = Substitution {
+                nesting: &scoped_class,
+                root: Some(&scoped_class),
+            };
+
+            // If it does not contain current selector or root pseudo class, scope it to be the
+            // children of the root element.
+            // If selector starts with a pseudo-class, apply it to the root element.
+            if !self.has_nesting() && !self.has_root() {
                 w.push_str(&scoped_class);
 
-                // If selector starts with a pseudo-class, apply it to the root element.
-                // For other selectors, scope it to be the children of the root element.
-                if !joined_s.starts_with(':') {
+                if !self.starts_with_pseudo() {
                     w.push(' ');
                 }
-                w.push_str(&joined_s);
             }
 
-        // For global styles, if it contains &, it will be replaced with html.
-        } else if joined_s.contains('&') {
-            w.push_str(&joined_s.replace('&', ":root"));
-        // For other styles, it will be written as is.
+            self.write_parts(w, &subst, ctx);
+
+        // For global styles, current selector will be replaced with :root.
         } else {
-            w.push_str(&joined_s);
+            let subst = Substitution {
+                nesting: ":root",
+                root: None,
+            };
+
+            self.write_parts(w, &subst, ctx);
         }
     }
 }
 
-impl<T: Into<Cow<'static, [StringFragment]>>> From<T> for Selector {
-    fn from(s: T) -> Self {
+impl From<Vec<CompoundSelector>> for Selector {
+    fn from(s: Vec<CompoundSelector>) -> Self {
         Self {
-            fragments: s.into(),
+            compounds: s.into(),
+            span: None,
+        }
+    }
+}
+
+impl From<Vec<SimpleSelector>> for CompoundSelector {
+    fn from(s: Vec<SimpleSelector>) -> Self {
+        Self {
+            combinator: None,
+            selectors: s.into(),
         }
     }
 }
@@ -65,9 +435,20 @@ impl<T: Into<Cow<'static, [StringFragment]>>> From<T> for Selector {
 mod tests {
     use super::*;
 
+    fn name(s: &'static str) -> Cow<'static, [StringFragment]> {
+        vec![s.into()].into()
+    }
+
+    fn pseudo_class(s: &'static str) -> SimpleSelector {
+        SimpleSelector::PseudoClass {
+            name: name(s),
+            argument: None,
+        }
+    }
+
     #[test]
     fn test_selector_gen_simple() {
-        let s: Selector = vec![".abc".into()].into();
+        let s: Selector = vec![vec![SimpleSelector::Class(name("abc"))].into()].into();
 
         assert_eq!(
             s.to_style_str(Some("stylist-abcdefgh")),
@@ -77,7 +458,7 @@ mod tests {
 
     #[test]
     fn test_selector_pseduo() {
-        let s: Selector = vec![":hover".into()].into();
+        let s: Selector = vec![vec![pseudo_class("hover")].into()].into();
 
         assert_eq!(
             s.to_style_str(Some("stylist-abcdefgh")),
@@ -87,7 +468,8 @@ mod tests {
 
     #[test]
     fn test_selector_root_pseduo() {
-        let s: Selector = vec![":root.big".into()].into();
+        let s: Selector =
+            vec![vec![pseudo_class("root"), SimpleSelector::Class(name("big"))].into()].into();
 
         assert_eq!(
             s.to_style_str(Some("stylist-abcdefgh")),
@@ -97,11 +479,96 @@ mod tests {
 
     #[test]
     fn test_selector_gen_current() {
-        let s: Selector = vec!["&.big".into()].into();
+        let s: Selector =
+            vec![vec![SimpleSelector::Nesting, SimpleSelector::Class(name("big"))].into()].into();
 
         assert_eq!(
             s.to_style_str(Some("stylist-abcdefgh")),
             ".stylist-abcdefgh.big"
         );
     }
+
+    #[test]
+    fn test_selector_tokens_in_attributes() {
+        let s: Selector = vec![
+            vec![SimpleSelector::Attribute(name(r#"data-x=":root""#))].into(),
+            CompoundSelector {
+                combinator: Some(Combinator::Child),
+                selectors: vec![
+                    SimpleSelector::Type(name("a")),
+                    SimpleSelector::Attribute(name(r#"href*="&""#)),
+                ]
+                .into(),
+            },
+        ]
+        .into();
+
+        assert_eq!(
+            s.to_style_str(Some("stylist-abcdefgh")),
+            r#".stylist-abcdefgh [data-x=":root"] > a[href*="&"]"#
+        );
+        assert_eq!(s.to_style_str(None), r#"[data-x=":root"] > a[href*="&"]"#);
+    }
+
+    #[test]
+    fn test_selector_nesting_in_argument() {
+        let s: Selector = vec![vec![
+            SimpleSelector::Type(name("a")),
+            SimpleSelector::PseudoClass {
+                name: name("not"),
+                argument: Some(PseudoArgument::Selectors(
+                    vec![vec![vec![
+                        SimpleSelector::Nesting,
+                        SimpleSelector::Class(name("active")),
+                    ]
+                    .into()]
+                    .into()]
+                    .into(),
+                )),
+            },
+        ]
+        .into()]
+        .into();
+
+        assert_eq!(
+            s.to_style_str(Some("stylist-abcdefgh")),
+            "a:not(.stylist-abcdefgh.active)"
+        );
+        assert_eq!(s.to_style_str(None), "a:not(:root.active)");
+    }
+
+    #[test]
+    fn test_selector_nested() {
+        let mut ctx = StyleContext::new(Some("stylist-abcdefgh"));
+        let parent = ".stylist-abcdefgh .card";
+
+        let mut s = String::new();
+        Selector::from(vec![vec![SimpleSelector::Class(name("title"))].into()])
+            .write_nested_style(&mut s, parent, &mut ctx);
+        assert_eq!(s, ".stylist-abcdefgh .card .title");
+
+        let mut s = String::new();
+        Selector::from(vec![vec![pseudo_class("hover")].into()])
+            .write_nested_style(&mut s, parent, &mut ctx);
+        assert_eq!(s, ".stylist-abcdefgh .card:hover");
+
+        let mut s = String::new();
+        Selector::from(vec![CompoundSelector {
+            combinator: Some(Combinator::Child),
+            selectors: vec![SimpleSelector::Type(name("a"))].into(),
+        }])
+        .write_nested_style(&mut s, parent, &mut ctx);
+        assert_eq!(s, ".stylist-abcdefgh .card > a");
+
+        let mut s = String::new();
+        Selector::from(vec![
+            vec![pseudo_class("root"), SimpleSelector::Class(name("dark"))].into(),
+            CompoundSelector {
+                combinator: Some(Combinator::Descendant),
+                selectors: vec![SimpleSelector::Nesting].into(),
+            },
+        ])
+        .write_nested_style(&mut s, parent, &mut ctx);
+        assert_eq!(s, ".stylist-abcdefgh.dark .stylist-abcdefgh .card");
+    }
 }
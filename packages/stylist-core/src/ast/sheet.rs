@@ -44,6 +44,16 @@ impl Sheet {
     pub fn new() -> Self {
         Self(Arc::new(Cow::Borrowed(&[])))
     }
+
+    /// Returns the names of all keyframes defined in this stylesheet, without quotes.
+    pub fn keyframes(&self) -> Vec<String> {
+        let mut keyframes = Vec::new();
+        for scope in self.0.iter() {
+            scope.collect_keyframes(&mut keyframes);
+        }
+
+        keyframes
+    }
 }
 
 impl From<Vec<ScopeContent>> for Sheet {
@@ -72,6 +82,10 @@ impl Default for Sheet {
 
 impl ToStyleStr for Sheet {
     fn write_style(&self, w: &mut String, ctx: &mut StyleContext<'_>) {
+        if ctx.class_name.is_some() {
+            ctx.set_keyframes(self.keyframes());
+        }
+
         for scope in self.0.iter() {
             scope.write_style(w, ctx);
         }
@@ -81,33 +95,39 @@ impl ToStyleStr for Sheet {
 #[cfg_attr(documenting, doc(cfg(feature = "parser")))]
 #[cfg(feature = "parser")]
 mod feat_parser {
-    use once_cell::sync::Lazy;
-    use std::collections::HashMap;
     use std::str::FromStr;
-    use std::sync::{Arc, Mutex};
 
     use super::*;
-
-    static CACHED_SHEETS: Lazy<Arc<Mutex<HashMap<String, Sheet>>>> = Lazy::new(Arc::default);
+    use crate::ast::SheetCache;
 
     impl FromStr for Sheet {
         type Err = crate::Error;
 
+        /// Parses a sheet using the [current](SheetCache::current) cache.
         fn from_str(s: &str) -> crate::Result<Self> {
-            use crate::parser::Parser;
-
-            let cached_sheets = CACHED_SHEETS.clone();
-            let mut cached_sheets = cached_sheets.lock().unwrap();
-
-            if let Some(m) = cached_sheets.get(s) {
-                return Ok(m.clone());
-            }
-
-            let m: Sheet = Parser::parse(s)?;
-
-            cached_sheets.insert(s.to_string(), m.clone());
+            SheetCache::current().parse(s)
+        }
+    }
 
-            Ok(m)
+    impl Sheet {
+        /// Parses a stylesheet, skipping invalid declarations and blocks instead of failing.
+        ///
+        /// Returns the parsed sheet together with the problems found while parsing it.
+        /// Sheets parsed with this method are not cached.
+        ///
+        /// # Examples
+        ///
+        /// ```
+        /// use stylist_core::ast::Sheet;
+        ///
+        /// let (sheet, diagnostics) = Sheet::parse_with_diagnostics("color red; width: 1px;");
+        ///
+        /// assert_eq!(sheet.len(), 1);
+        /// assert_eq!(diagnostics.len(), 1);
+        /// assert_eq!(diagnostics[0].column, 1);
+        /// ```
+        pub fn parse_with_diagnostics(s: &str) -> (Self, Vec<crate::ParseErrorDetails>) {
+            crate::parser::Parser::parse_lossy(s)
         }
     }
 }
@@ -0,0 +1,276 @@
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex};
+
+use once_cell::sync::Lazy;
+
+use super::Sheet;
+use crate::parser::Parser;
+
+/// The capacity of the global cache.
+const DEFAULT_CAPACITY: usize = 1024;
+
+static GLOBAL: Lazy<SheetCache> = Lazy::new(|| SheetCache::new(DEFAULT_CAPACITY));
+
+thread_local! {
+    static CURRENT: RefCell<Option<SheetCache>> = const { RefCell::new(None) };
+}
+
+/// Statistics of a [`SheetCache`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct SheetCacheStats {
+    /// The number of lookups that returned a cached sheet.
+    pub hits: u64,
+    /// The number of lookups that had to parse the sheet.
+    pub misses: u64,
+    /// The number of sheets currently in the cache.
+    pub len: usize,
+    /// The maximum number of sheets the cache holds.
+    pub capacity: usize,
+}
+
+#[derive(Debug, Default)]
+struct SheetCacheInner {
+    capacity: usize,
+    sheets: HashMap<Arc<str>, (Sheet, u64)>,
+    // Keys by the tick they were last used at, the least recently used one comes first.
+    recent: BTreeMap<u64, Arc<str>>,
+    tick: u64,
+
+    hits: u64,
+    misses: u64,
+}
+
+impl SheetCacheInner {
+    fn get(&mut self, css: &str) -> Option<Sheet> {
+        self.tick += 1;
+        let tick = self.tick;
+
+        let (key, (sheet, last_used)) = self.sheets.get_key_value(css)?;
+        let key = key.clone();
+        let sheet = sheet.clone();
+
+        self.recent.remove(last_used);
+        self.recent.insert(tick, key.clone());
+        if let Some(m) = self.sheets.get_mut(&key) {
+            m.1 = tick;
+        }
+
+        Some(sheet)
+    }
+
+    fn insert(&mut self, css: &str, sheet: Sheet) {
+        if self.capacity == 0 {
+            return;
+        }
+
+        self.tick += 1;
+        let key: Arc<str> = css.into();
+
+        if let Some((_, last_used)) = self.sheets.insert(key.clone(), (sheet, self.tick)) {
+            self.recent.remove(&last_used);
+        }
+        self.recent.insert(self.tick, key);
+
+        self.evict();
+    }
+
+    fn evict(&mut self) {
+        while self.sheets.len() > self.capacity {
+            match self.recent.pop_first() {
+                Some((_, key)) => {
+                    self.sheets.remove(&key);
+                }
+                None => break,
+            }
+        }
+    }
+}
+
+/// A bounded cache of parsed stylesheets.
+///
+/// When the cache is full, the least recently used sheet is evicted. Clones of a cache share
+/// the same storage.
+///
+/// [`Sheet::from_str`](std::str::FromStr::from_str) uses the cache returned by
+/// [`SheetCache::current`], which is the global cache unless another cache is entered with
+/// [`SheetCache::scope`].
+///
+/// # Examples
+///
+/// ```
+/// use stylist_core::ast::SheetCache;
+///
+/// let cache = SheetCache::new(16);
+///
+/// cache.parse("color: red;")?;
+/// cache.parse("color: red;")?;
+///
+/// let stats = cache.stats();
+/// assert_eq!(stats.hits, 1);
+/// assert_eq!(stats.misses, 1);
+/// # Ok::<(), stylist_core::Error>(())
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct SheetCache {
+    inner: Arc<Mutex<SheetCacheInner>>,
+}
+
+impl PartialEq for SheetCache {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.inner, &other.inner)
+    }
+}
+
+impl SheetCache {
+    /// Creates a cache that holds at most `capacity` sheets.
+    ///
+    /// A capacity of 0 disables caching.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(SheetCacheInner {
+                capacity,
+                ..Default::default()
+            })),
+        }
+    }
+
+    /// Returns the global cache.
+    pub fn global() -> Self {
+        GLOBAL.clone()
+    }
+
+    /// Returns the cache used by the current thread to parse sheets.
+    pub fn current() -> Self {
+        CURRENT
+            .with(|m| m.borrow().clone())
+            .unwrap_or_else(Self::global)
+    }
+
+    /// Runs `f` with this cache as the [current](SheetCache::current) cache of this thread.
+    pub fn scope<F, R>(&self, f: F) -> R
+    where
+        F: FnOnce() -> R,
+    {
+        struct Restore(Option<SheetCache>);
+
+        impl Drop for Restore {
+            fn drop(&mut self) {
+                let prev = self.0.take();
+                CURRENT.with(move |m| *m.borrow_mut() = prev);
+            }
+        }
+
+        let _restore = Restore(CURRENT.with(|m| m.borrow_mut().replace(self.clone())));
+
+        f()
+    }
+
+    /// Parses a sheet, returning the cached sheet if the same string has been parsed before.
+    pub fn parse(&self, css: &str) -> crate::Result<Sheet> {
+        {
+            let mut inner = self.inner.lock().unwrap();
+            if let Some(m) = inner.get(css) {
+                inner.hits += 1;
+                return Ok(m);
+            }
+        }
+
+        // The lock is not held while parsing so other threads can use the cache meanwhile.
+        let sheet = Parser::parse(css)?;
+
+        let mut inner = self.inner.lock().unwrap();
+        inner.misses += 1;
+        inner.insert(css, sheet.clone());
+
+        Ok(sheet)
+    }
+
+    /// Returns the maximum number of sheets the cache holds.
+    pub fn capacity(&self) -> usize {
+        self.inner.lock().unwrap().capacity
+    }
+
+    /// Sets the maximum number of sheets the cache holds, evicting sheets if necessary.
+    ///
+    /// A capacity of 0 disables caching.
+    pub fn set_capacity(&self, capacity: usize) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.capacity = capacity;
+        inner.evict();
+    }
+
+    /// Removes all sheets from the cache.
+    ///
+    /// The statistics are kept.
+    pub fn clear(&self) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.sheets.clear();
+        inner.recent.clear();
+    }
+
+    /// Returns the statistics of the cache.
+    pub fn stats(&self) -> SheetCacheStats {
+        let inner = self.inner.lock().unwrap();
+
+        SheetCacheStats {
+            hits: inner.hits,
+            misses: inner.misses,
+            len: inner.sheets.len(),
+            capacity: inner.capacity,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_eviction() -> crate::Result<()> {
+        let cache = SheetCache::new(2);
+
+        cache.parse("color: red;")?;
+        cache.parse("color: blue;")?;
+        // Uses red, so blue becomes the least recently used sheet.
+        cache.parse("color: red;")?;
+        cache.parse("color: green;")?;
+
+        assert_eq!(
+            cache.stats(),
+            SheetCacheStats {
+                hits: 1,
+                misses: 3,
+                len: 2,
+                capacity: 2,
+            }
+        );
+
+        cache.parse("color: red;")?;
+        cache.parse("color: blue;")?;
+        assert_eq!(cache.stats().hits, 2);
+        assert_eq!(cache.stats().misses, 4);
+
+        cache.set_capacity(0);
+        cache.parse("color: blue;")?;
+        assert_eq!(cache.stats().len, 0);
+        assert_eq!(cache.stats().misses, 5);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_scope() -> crate::Result<()> {
+        let cache = SheetCache::new(4);
+
+        let sheet: Sheet = cache.scope(|| "width: 1px;".parse())?;
+        assert_eq!(cache.stats().len, 1);
+        assert_eq!(SheetCache::current(), SheetCache::global());
+
+        cache.clear();
+        assert_eq!(cache.stats().len, 0);
+        assert_eq!(cache.parse("width: 1px;")?, sheet);
+
+        Ok(())
+    }
+}
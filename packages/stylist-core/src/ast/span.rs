@@ -0,0 +1,51 @@
+/// A byte range in the source of a stylesheet.
+///
+/// Spans are recorded by the parser and are ignored when comparing or hashing AST nodes, so the
+/// same style parsed at different positions is still considered the same.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Span {
+    /// The byte offset of the first character.
+    pub start: usize,
+    /// The byte offset after the last character.
+    pub end: usize,
+}
+
+impl Span {
+    /// Creates a new span.
+    pub fn new(start: usize, end: usize) -> Self {
+        Self { start, end }
+    }
+
+    /// Returns the 1-based line and column of the start of the span in the source.
+    ///
+    /// The column is counted in characters.
+    pub fn line_column(&self, source: &str) -> (usize, usize) {
+        let before = &source[..self.start.min(source.len())];
+        let line_start = before.rfind('\n').map(|m| m + 1).unwrap_or(0);
+
+        (
+            before.matches('\n').count() + 1,
+            before[line_start..].chars().count() + 1,
+        )
+    }
+
+    /// Returns the source of the span.
+    pub fn source_text<'a>(&self, source: &'a str) -> &'a str {
+        &source[self.start..self.end]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_line_column() {
+        let source = "color: red;\n.a {\n    width: 1px;\n}";
+
+        assert_eq!(Span::new(0, 5).line_column(source), (1, 1));
+        assert_eq!(Span::new(12, 14).line_column(source), (2, 1));
+        assert_eq!(Span::new(21, 32).line_column(source), (3, 5));
+        assert_eq!(Span::new(21, 32).source_text(source), "width: 1px;");
+    }
+}
@@ -1,17 +1,68 @@
 use std::borrow::Cow;
+use std::hash::{Hash, Hasher};
 
 use serde::{Deserialize, Serialize};
 
-use super::{StringFragment, StyleContext, ToStyleStr};
+use super::{ComponentValue, Span, StringFragment, StyleContext, ToStyleStr};
 
 /// A simple CSS property in the form of a key value pair. Mirrors what would
 /// be called a "Declaration" in the CSS standard.
 ///
-/// E.g.: `color: red`
-#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+/// E.g.: `color: red !important`
+///
+/// The value is stored as written, along with its [`ComponentValue`]s if it has no
+/// interpolations, see [`component_values`](Self::component_values).
+#[derive(Debug, Clone, Eq, Serialize, Deserialize)]
 pub struct StyleAttribute {
     pub key: Cow<'static, str>,
+    /// The value of the declaration, without `!important`.
     pub value: Cow<'static, [StringFragment]>,
+    /// Whether the declaration is marked as `!important`.
+    pub important: bool,
+    /// The value tokenized into component values when it is parsed.
+    ///
+    /// `None` if the value contains interpolations, which are only known at runtime.
+    pub component_values: Option<Cow<'static, [ComponentValue]>>,
+    /// The position of this declaration in the source, if it is parsed at runtime.
+    #[serde(skip)]
+    pub span: Option<Span>,
+}
+
+// Two declarations are the same as long as they render the same, regardless of the span and the
+// component values, which are derived from the value.
+impl PartialEq for StyleAttribute {
+    fn eq(&self, other: &Self) -> bool {
+        self.key == other.key && self.value == other.value && self.important == other.important
+    }
+}
+
+impl Hash for StyleAttribute {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.key.hash(state);
+        self.value.hash(state);
+        self.important.hash(state);
+    }
+}
+
+impl StyleAttribute {
+    /// Returns the value of the declaration as component values.
+    ///
+    /// Values with interpolations are tokenized on demand, the others are tokenized once when
+    /// they are parsed.
+    pub fn component_values(&self) -> Cow<'_, [ComponentValue]> {
+        match self.component_values {
+            Some(ref m) => Cow::Borrowed(m),
+            None => {
+                let value = self
+                    .value
+                    .iter()
+                    .map(|m| m.inner.as_ref())
+                    .collect::<String>();
+
+                Cow::Owned(ComponentValue::tokenize(&value))
+            }
+        }
+    }
 }
 
 impl ToStyleStr for StyleAttribute {
@@ -21,12 +72,37 @@ impl ToStyleStr for StyleAttribute {
         ctx.write_padding(w);
 
         w.push_str(&self.key);
-        w.push_str(": ");
+        w.push_str(if ctx.options().minify { ":" } else { ": " });
+
+        let key = self.key.strip_prefix("-webkit-").unwrap_or(&self.key);
+        if key == "animation" || key == "animation-name" {
+            let mut value = String::new();
+            for i in self.value.iter() {
+                i.write_style(&mut value, ctx);
+            }
+
+            // Rename keyframes that are defined in the same stylesheet.
+            let mut last = 0;
+            for (index, sep) in value.match_indices(|c: char| c.is_whitespace() || c == ',') {
+                w.push_str(&ctx.keyframes_name(&value[last..index]));
+                w.push_str(sep);
+                last = index + sep.len();
+            }
+            w.push_str(&ctx.keyframes_name(&value[last..]));
+        } else {
+            for i in self.value.iter() {
+                i.write_style(w, ctx);
+            }
+        }
 
-        for i in self.value.iter() {
-            i.write_style(w, ctx);
+        if self.important {
+            w.push_str(if ctx.options().minify {
+                "!important"
+            } else {
+                " !important"
+            });
         }
 
-        w.push_str(";\n");
+        ctx.write_declaration_end(w);
     }
 }
@@ -1,13 +1,23 @@
-use super::StyleContext;
+use super::{OutputOptions, StyleContext};
 
 /// Structs implementing this trait should be able to turn into
 /// a part of a CSS style sheet.
 pub trait ToStyleStr {
     fn to_style_str(&self, class_name: Option<&str>) -> String {
+        self.to_style_str_with_options(class_name, OutputOptions::default())
+    }
+
+    /// Writes the style with the given output options.
+    fn to_style_str_with_options(
+        &self,
+        class_name: Option<&str>,
+        options: OutputOptions,
+    ) -> String {
         let mut s = String::new();
-        let mut ctx = StyleContext::new(class_name);
+        let mut ctx = StyleContext::new_with_options(class_name, options);
 
         self.write_style(&mut s, &mut ctx);
+        ctx.finish(&mut s);
 
         s
     }
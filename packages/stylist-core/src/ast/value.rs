@@ -0,0 +1,317 @@
+use std::borrow::Cow;
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+/// A component value of a declaration value.
+///
+/// E.g.: `1px solid rgb(0, 0, 0)` consists of a dimension, an ident and a function.
+///
+/// Declaration values are stored in the AST as [`StringFragment`](super::StringFragment)s, along
+/// with their component values unless they contain interpolations that are only known at runtime,
+/// see [`StyleAttribute::component_values`](super::StyleAttribute::component_values).
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ComponentValue {
+    /// An identifier, e.g.: `solid`, `--my-color`.
+    Ident(Cow<'static, str>),
+    /// A number without unit, e.g.: `1.5`.
+    Number(Cow<'static, str>),
+    /// A number with unit, e.g.: `10px`.
+    Dimension {
+        value: Cow<'static, str>,
+        unit: Cow<'static, str>,
+    },
+    /// A percentage, e.g.: `50%`. The percent sign is not included.
+    Percentage(Cow<'static, str>),
+    /// A quoted string, including its quotes.
+    String(Cow<'static, str>),
+    /// An unquoted url, e.g.: `url(image.png)`. The `url(` and `)` are not included.
+    ///
+    /// Quoted urls are represented as a function with a string argument.
+    Url(Cow<'static, str>),
+    /// A hash, e.g.: `#fff`. The hash sign is not included.
+    Hash(Cow<'static, str>),
+    /// A function, e.g.: `calc(100% - 10px)`.
+    Function {
+        name: Cow<'static, str>,
+        args: Vec<ComponentValue>,
+    },
+    /// Any other character, e.g.: `,` or `/`.
+    Delim(char),
+    /// One or more whitespace characters.
+    Whitespace,
+}
+
+impl ComponentValue {
+    /// Splits a declaration value into component values.
+    pub fn tokenize(value: &str) -> Vec<ComponentValue> {
+        Tokenizer { rest: value }.tokens(false)
+    }
+}
+
+impl fmt::Display for ComponentValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Ident(ref m) | Self::Number(ref m) | Self::String(ref m) => f.write_str(m),
+            Self::Dimension {
+                ref value,
+                ref unit,
+            } => write!(f, "{value}{unit}"),
+            Self::Percentage(ref m) => write!(f, "{m}%"),
+            Self::Url(ref m) => write!(f, "url({m})"),
+            Self::Hash(ref m) => write!(f, "#{m}"),
+            Self::Function { ref name, ref args } => {
+                write!(f, "{name}(")?;
+                for arg in args.iter() {
+                    arg.fmt(f)?;
+                }
+                f.write_str(")")
+            }
+            Self::Delim(m) => write!(f, "{m}"),
+            Self::Whitespace => f.write_str(" "),
+        }
+    }
+}
+
+fn is_name_start(c: char) -> bool {
+    c.is_ascii_alphabetic() || c == '_' || !c.is_ascii()
+}
+
+fn is_name_char(c: char) -> bool {
+    is_name_start(c) || c.is_ascii_digit() || c == '-'
+}
+
+fn starts_ident(s: &str) -> bool {
+    let mut chars = s.chars();
+    match chars.next() {
+        Some('-') => matches!(chars.next(), Some(c) if is_name_start(c) || c == '-' || c == '\\'),
+        Some('\\') => chars.next().is_some(),
+        Some(c) => is_name_start(c),
+        None => false,
+    }
+}
+
+fn starts_number(s: &str) -> bool {
+    let s = s.strip_prefix(['+', '-']).unwrap_or(s);
+    let s = s.strip_prefix('.').unwrap_or(s);
+    s.starts_with(|c: char| c.is_ascii_digit())
+}
+
+struct Tokenizer<'a> {
+    rest: &'a str,
+}
+
+impl<'a> Tokenizer<'a> {
+    fn peek(&self) -> Option<char> {
+        self.rest.chars().next()
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.rest = &self.rest[c.len_utf8()..];
+        Some(c)
+    }
+
+    fn take_while(&mut self, f: impl Fn(char) -> bool) -> &'a str {
+        let len = self.rest.find(|c| !f(c)).unwrap_or(self.rest.len());
+        let (taken, rest) = self.rest.split_at(len);
+        self.rest = rest;
+        taken
+    }
+
+    fn take_digits(&mut self) -> &'a str {
+        self.take_while(|c| c.is_ascii_digit())
+    }
+
+    fn take_name(&mut self) -> &'a str {
+        let start = self.rest;
+        while let Some(c) = self.peek() {
+            if c == '\\' {
+                self.bump();
+                self.bump();
+            } else if is_name_char(c) {
+                self.bump();
+            } else {
+                break;
+            }
+        }
+        &start[..start.len() - self.rest.len()]
+    }
+
+    fn take_string(&mut self, quote: char) -> &'a str {
+        let start = self.rest;
+        self.bump();
+        while let Some(c) = self.bump() {
+            if c == '\\' {
+                self.bump();
+            } else if c == quote {
+                break;
+            }
+        }
+        &start[..start.len() - self.rest.len()]
+    }
+
+    fn take_number(&mut self) -> &'a str {
+        let start = self.rest;
+        if self.rest.starts_with(['+', '-']) {
+            self.bump();
+        }
+        self.take_digits();
+        if self.rest.starts_with('.') && starts_number(&self.rest[1..]) {
+            self.bump();
+            self.take_digits();
+        }
+
+        // Only consume the exponent if it is followed by digits, otherwise it is a unit.
+        if let Some(exp) = self.rest.strip_prefix(['e', 'E']) {
+            let exp = exp.strip_prefix(['+', '-']).unwrap_or(exp);
+            if exp.starts_with(|c: char| c.is_ascii_digit()) {
+                self.rest = exp;
+                self.take_digits();
+            }
+        }
+        &start[..start.len() - self.rest.len()]
+    }
+
+    fn tokens(&mut self, nested: bool) -> Vec<ComponentValue> {
+        let mut tokens = Vec::new();
+
+        while let Some(c) = self.peek() {
+            if nested && c == ')' {
+                self.bump();
+                break;
+            }
+            tokens.push(self.token(c));
+        }
+
+        tokens
+    }
+
+    fn token(&mut self, c: char) -> ComponentValue {
+        if c.is_whitespace() {
+            self.take_while(char::is_whitespace);
+            return ComponentValue::Whitespace;
+        }
+
+        if c == '"' || c == '\'' {
+            return ComponentValue::String(self.take_string(c).to_string().into());
+        }
+
+        if starts_number(self.rest) {
+            let value = self.take_number().to_string().into();
+
+            if self.rest.starts_with('%') {
+                self.bump();
+                return ComponentValue::Percentage(value);
+            }
+
+            if starts_ident(self.rest) {
+                let unit = self.take_name().to_string().into();
+                return ComponentValue::Dimension { value, unit };
+            }
+
+            return ComponentValue::Number(value);
+        }
+
+        if starts_ident(self.rest) {
+            let name = self.take_name();
+
+            if !self.rest.starts_with('(') {
+                return ComponentValue::Ident(name.to_string().into());
+            }
+            self.bump();
+
+            let is_quoted = self.rest.trim_start().starts_with(['"', '\'']);
+            if name.eq_ignore_ascii_case("url") && !is_quoted {
+                let url = self.take_while(|c| c != ')').trim().to_string().into();
+                self.bump();
+                return ComponentValue::Url(url);
+            }
+
+            return ComponentValue::Function {
+                name: name.to_string().into(),
+                args: self.tokens(true),
+            };
+        }
+
+        if c == '#' && self.rest[1..].starts_with(|c| is_name_char(c) || c == '\\') {
+            self.bump();
+            return ComponentValue::Hash(self.take_name().to_string().into());
+        }
+
+        self.bump();
+        ComponentValue::Delim(c)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ident(m: &'static str) -> ComponentValue {
+        ComponentValue::Ident(m.into())
+    }
+
+    fn dimension(value: &'static str, unit: &'static str) -> ComponentValue {
+        ComponentValue::Dimension {
+            value: value.into(),
+            unit: unit.into(),
+        }
+    }
+
+    #[test]
+    fn test_tokenize() {
+        assert_eq!(
+            ComponentValue::tokenize("1px solid #fff"),
+            vec![
+                dimension("1", "px"),
+                ComponentValue::Whitespace,
+                ident("solid"),
+                ComponentValue::Whitespace,
+                ComponentValue::Hash("fff".into()),
+            ]
+        );
+
+        assert_eq!(
+            ComponentValue::tokenize("calc(50% - -.5em)"),
+            vec![ComponentValue::Function {
+                name: "calc".into(),
+                args: vec![
+                    ComponentValue::Percentage("50".into()),
+                    ComponentValue::Whitespace,
+                    ComponentValue::Delim('-'),
+                    ComponentValue::Whitespace,
+                    dimension("-.5", "em"),
+                ],
+            }]
+        );
+
+        assert_eq!(
+            ComponentValue::tokenize("url(a.png), url(\"b.png\") 1e3 --x"),
+            vec![
+                ComponentValue::Url("a.png".into()),
+                ComponentValue::Delim(','),
+                ComponentValue::Whitespace,
+                ComponentValue::Function {
+                    name: "url".into(),
+                    args: vec![ComponentValue::String("\"b.png\"".into())],
+                },
+                ComponentValue::Whitespace,
+                ComponentValue::Number("1e3".into()),
+                ComponentValue::Whitespace,
+                ident("--x"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_tokenize_roundtrip() {
+        let value = "rgba(0.32, 0, 0, 15%) 'a\\'b' 3px/1.5 -webkit-box";
+        let s = ComponentValue::tokenize(value)
+            .iter()
+            .map(|m| m.to_string())
+            .collect::<String>();
+
+        assert_eq!(s, value);
+    }
+}
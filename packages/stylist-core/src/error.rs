@@ -28,6 +28,8 @@ pub enum Error {
         reason: String,
         #[source]
         source: Option<nom::error::VerboseError<String>>,
+        /// The position of the error in the source, if known.
+        details: Option<ParseErrorDetails>,
     },
 
     /// Failed to interact with Web API.
@@ -44,6 +46,42 @@ pub enum Error {
     ReadFailed,
 }
 
+/// The position and the cause of a CSS parse error.
+///
+/// This can be used to render the error in a way that is suitable for the user, e.g.: to
+/// highlight the position of the error in an editor.
+#[cfg_attr(documenting, doc(cfg(feature = "parser")))]
+#[cfg(feature = "parser")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseErrorDetails {
+    /// The byte offset of the error in the source.
+    pub offset: usize,
+    /// The 1-based line of the error.
+    pub line: usize,
+    /// The 1-based column of the error, counted in characters.
+    pub column: usize,
+    /// What the parser expected at the position, if known, e.g.: `'}'` or `StyleAttribute`.
+    pub expected: Option<String>,
+    /// The line of the source that contains the error.
+    pub snippet: String,
+}
+
+#[cfg(feature = "parser")]
+impl ParseErrorDetails {
+    pub(crate) fn new(source: &str, offset: usize, expected: Option<String>) -> Self {
+        let (line, column) = crate::ast::Span::new(offset, offset).line_column(source);
+        let snippet = source.lines().nth(line - 1).unwrap_or_default().to_string();
+
+        Self {
+            offset,
+            line,
+            column,
+            expected,
+            snippet,
+        }
+    }
+}
+
 impl From<std::convert::Infallible> for Error {
     fn from(infallible: std::convert::Infallible) -> Self {
         match infallible {}
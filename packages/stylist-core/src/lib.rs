@@ -7,6 +7,8 @@
 #![cfg_attr(any(releasing, not(debug_assertions)), deny(dead_code, unused_imports))]
 
 mod error;
+#[cfg(feature = "parser")]
+pub use error::ParseErrorDetails;
 pub use error::{Error, Result, ResultDisplay};
 pub mod ast;
 pub mod bow;
@@ -75,8 +77,6 @@ mod tests {
     .test-style-cls header, .test-style-cls footer {
         max-width: 500px;
     }
-}
-@supports (max-width: 500px) {
     @media screen and (max-width: 500px) {
         .test-style-cls header, .test-style-cls footer {
             display: flex;
@@ -87,4 +87,268 @@ mod tests {
 "#,
         )
     }
+
+    #[test]
+    fn test_scoped_nested() {
+        let style: Sheet = r#"
+                .card {
+                    color: red;
+
+                    .title, .subtitle {
+                        font-weight: bold;
+
+                        &:hover {
+                            color: blue;
+                        }
+                    }
+
+                    @media print {
+                        > a {
+                            display: none;
+                        }
+                    }
+
+                    background-color: white;
+                }
+
+                header, footer {
+                    & + &, & span {
+                        color: yellow;
+                    }
+                }
+
+                .a .b {
+                    div&, .x&, &.y {
+                        color: green;
+                    }
+                }
+            "#
+        .parse()
+        .expect("Failed to create Style.");
+
+        assert_eq!(
+            style.to_style_str(Some("test-style-cls")),
+            r#".test-style-cls .card {
+    color: red;
+}
+.test-style-cls .card .title, .test-style-cls .card .subtitle {
+    font-weight: bold;
+}
+.test-style-cls .card .title:hover, .test-style-cls .card .subtitle:hover {
+    color: blue;
+}
+@media print {
+    .test-style-cls .card > a {
+        display: none;
+    }
+}
+.test-style-cls .card {
+    background-color: white;
+}
+:is(.test-style-cls header, .test-style-cls footer) + :is(.test-style-cls header, .test-style-cls footer), .test-style-cls header span, .test-style-cls footer span {
+    color: yellow;
+}
+div:is(.test-style-cls .a .b), .x:is(.test-style-cls .a .b), .test-style-cls .a .b.y {
+    color: green;
+}
+"#,
+        )
+    }
+
+    #[test]
+    fn test_scoped_layer() {
+        let style: Sheet = r#"
+                @layer base, components;
+
+                @layer base {
+                    color: red;
+
+                    .title {
+                        @layer components;
+                        color: blue;
+                    }
+                }
+
+                @media print {
+                    @layer print;
+                }
+            "#
+        .parse()
+        .expect("Failed to create Style.");
+
+        assert_eq!(
+            style.to_style_str(Some("test-style-cls")),
+            r#"@layer base, components;
+@layer base {
+    .test-style-cls {
+        color: red;
+    }
+}
+@layer base {
+    @layer components;
+}
+@layer base {
+    .test-style-cls .title {
+        color: blue;
+    }
+}
+@media print {
+    @layer print;
+}
+"#,
+        )
+    }
+
+    #[test]
+    fn test_scoped_keyframes() {
+        let style: Sheet = r#"
+                @keyframes fade {
+                    from {
+                        opacity: 0;
+                    }
+                }
+
+                animation: fade 1s ease-in, spin 2s;
+
+                .inner {
+                    animation-name: fade;
+                }
+
+                @-webkit-keyframes "pulse" {
+                    to {
+                        opacity: 1;
+                    }
+                }
+
+                .quoted {
+                    -webkit-animation: "pulse" 2s;
+                    -webkit-animation-name: 'pulse', fade;
+                }
+            "#
+        .parse()
+        .expect("Failed to create Style.");
+
+        assert_eq!(
+            style.to_style_str(Some("test-style-cls")),
+            r#"@keyframes test-style-cls-fade {
+    from {
+        opacity: 0;
+    }
+}
+.test-style-cls {
+    animation: test-style-cls-fade 1s ease-in, spin 2s;
+}
+.test-style-cls .inner {
+    animation-name: test-style-cls-fade;
+}
+@-webkit-keyframes "test-style-cls-pulse" {
+    to {
+        opacity: 1;
+    }
+}
+.test-style-cls .quoted {
+    -webkit-animation: "test-style-cls-pulse" 2s;
+    -webkit-animation-name: 'test-style-cls-pulse', test-style-cls-fade;
+}
+"#,
+        );
+
+        assert_eq!(
+            style.to_style_str(None),
+            r#"@keyframes fade {
+    from {
+        opacity: 0;
+    }
+}
+:root {
+    animation: fade 1s ease-in, spin 2s;
+}
+.inner {
+    animation-name: fade;
+}
+@-webkit-keyframes "pulse" {
+    to {
+        opacity: 1;
+    }
+}
+.quoted {
+    -webkit-animation: "pulse" 2s;
+    -webkit-animation-name: 'pulse', fade;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn test_scoped_selector_tokens() {
+        let style: Sheet = r#"
+                [data-x=":root"] a[href*="&"], :root.dark > :is(&.a, b) {
+                    color: red;
+                }
+            "#
+        .parse()
+        .expect("Failed to create Style.");
+
+        assert_eq!(
+            style.to_style_str(Some("test-style-cls")),
+            r#".test-style-cls [data-x=":root"] a[href*="&"], .test-style-cls.dark > :is(.test-style-cls.a, b) {
+    color: red;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn test_merged_rules() {
+        let style: Sheet = r#"
+                .a {
+                    @media print {
+                        color: red;
+
+                        .b {
+                            color: blue;
+                        }
+                    }
+                    width: 1px;
+                    @media print {
+                        height: 1px;
+                    }
+                }
+                @font-face {
+                    font-family: a;
+                }
+                @font-face {
+                    font-family: b;
+                }
+            "#
+        .parse()
+        .expect("Failed to create Style.");
+
+        assert_eq!(
+            style.to_style_str(Some("test-style-cls")),
+            r#"@media print {
+    .test-style-cls .a {
+        color: red;
+    }
+    .test-style-cls .a .b {
+        color: blue;
+    }
+}
+.test-style-cls .a {
+    width: 1px;
+}
+@media print {
+    .test-style-cls .a {
+        height: 1px;
+    }
+}
+@font-face {
+    font-family: a;
+}
+@font-face {
+    font-family: b;
+}
+"#,
+        );
+    }
 }
@@ -3,28 +3,200 @@ use std::fmt;
 
 use nom::branch::{alt, Alt};
 use nom::bytes::complete::{is_not, tag, take_while1};
-use nom::character::complete::{alpha1, alphanumeric1, anychar, char, none_of};
-use nom::combinator::{fail, map, not, opt, recognize};
-use nom::error::{convert_error, ErrorKind, ParseError, VerboseError};
+use nom::character::complete::{alpha1, alphanumeric1, anychar, char, none_of, one_of};
+use nom::combinator::{consumed, cut, map, map_opt, not, opt, recognize, value, verify};
+use nom::error::{convert_error, ErrorKind, ParseError, VerboseError, VerboseErrorKind};
 use nom::multi::{many0, many1, separated_list1};
 use nom::sequence::{delimited, pair, preceded, separated_pair, terminated};
 use nom::IResult;
 
 use crate::ast::{
-    Block, Rule, RuleBlockContent, ScopeContent, Selector, Sheet, StringFragment, StyleAttribute,
+    Block, Combinator, ComponentValue, CompoundSelector, PseudoArgument, Rule, RuleBlockContent,
+    ScopeContent, Selector, Sheet, SimpleSelector, Span, StringFragment, StyleAttribute,
 };
 use crate::bow::Bow;
-use crate::{Error, Result};
+use crate::{Error, ParseErrorDetails, Result};
 
 #[cfg(test)]
 use log::trace;
 
+/// The value of a style attribute.
+#[derive(Debug)]
+struct StyleAttrValue {
+    value: StringFragment,
+    important: bool,
+    // `None` if the value contains interpolations.
+    component_values: Option<Vec<ComponentValue>>,
+}
+
+/// A token of a selector, before it is grouped into compound selectors.
+#[derive(Debug)]
+enum SelectorToken {
+    Simple(SimpleSelector),
+    Combinator(Combinator),
+    Whitespace,
+}
+
+impl SelectorToken {
+    fn into_selector(tokens: Vec<Self>) -> Option<Selector> {
+        let mut compounds = Vec::new();
+        let mut selectors = Vec::new();
+        let mut combinator = None;
+        let mut pending = None;
+
+        for token in tokens {
+            match token {
+                Self::Simple(m) => {
+                    if let Some(next) = pending.take() {
+                        if !selectors.is_empty() {
+                            compounds.push(CompoundSelector {
+                                combinator,
+                                selectors: std::mem::take(&mut selectors).into(),
+                            });
+                        }
+                        combinator = Some(next);
+                    }
+                    selectors.push(m);
+                }
+                Self::Combinator(m) => {
+                    // Two combinators in a row.
+                    if matches!(pending, Some(m) if m != Combinator::Descendant) {
+                        return None;
+                    }
+                    pending = Some(m);
+                }
+                // Whitespace before the first simple selector is not a combinator.
+                Self::Whitespace => {
+                    if !selectors.is_empty() && pending.is_none() {
+                        pending = Some(Combinator::Descendant);
+                    }
+                }
+            }
+        }
+
+        // A selector cannot be empty or end with a combinator.
+        if selectors.is_empty() || matches!(pending, Some(m) if m != Combinator::Descendant) {
+            return None;
+        }
+
+        compounds.push(CompoundSelector {
+            combinator,
+            selectors: selectors.into(),
+        });
+
+        Some(compounds.into())
+    }
+}
+
+/// The name and the argument of a pseudo-class or a pseudo-element.
+type Pseudo = (Cow<'static, [StringFragment]>, Option<PseudoArgument>);
+
 #[derive(Debug, PartialEq)]
 enum RuleBlockKind {
     Keyframes,
     Other,
 }
 
+/// Wrap a parser, recording the span of its output.
+///
+/// The start of the input is not known while parsing, so spans are recorded as distances from the
+/// end of the input. They are converted into offsets by [`SpanResolver`].
+fn spanned<'a, O, F>(
+    mut p: F,
+) -> impl FnMut(&'a str) -> IResult<&'a str, (O, Span), VerboseError<&'a str>>
+where
+    F: nom::Parser<&'a str, O, VerboseError<&'a str>>,
+{
+    move |i: &'a str| {
+        let (rest, output) = p.parse(i)?;
+        let consumed = &i[..i.len() - rest.len()];
+
+        // Surrounding whitespaces are not a part of the span.
+        let start = i.len() - (consumed.len() - consumed.trim_start().len());
+        let end = rest.len() + (consumed.len() - consumed.trim_end().len());
+
+        Ok((rest, (output, Span::new(start, end))))
+    }
+}
+
+/// Converts spans recorded by [`spanned`] into offsets of the source.
+struct SpanResolver {
+    /// The length of the parsed input.
+    len: usize,
+    /// The length of the source, which can be shorter if blocks are closed by the parser.
+    source_len: usize,
+}
+
+impl SpanResolver {
+    fn new(len: usize, source_len: usize) -> Self {
+        Self { len, source_len }
+    }
+
+    fn span(&self, span: &mut Option<Span>) {
+        if let Some(ref mut m) = span {
+            *m = Span::new(
+                (self.len - m.start).min(self.source_len),
+                (self.len - m.end).min(self.source_len),
+            );
+        }
+    }
+
+    fn selectors(&self, selectors: &mut Cow<'static, [Selector]>) {
+        for sel in selectors.to_mut().iter_mut() {
+            self.span(&mut sel.span);
+
+            for compound in sel.compounds.to_mut().iter_mut() {
+                for simple in compound.selectors.to_mut().iter_mut() {
+                    if let SimpleSelector::PseudoClass {
+                        argument: Some(PseudoArgument::Selectors(ref mut m)),
+                        ..
+                    }
+                    | SimpleSelector::PseudoElement {
+                        argument: Some(PseudoArgument::Selectors(ref mut m)),
+                        ..
+                    } = simple
+                    {
+                        self.selectors(m);
+                    }
+                }
+            }
+        }
+    }
+
+    fn block(&self, block: &mut Block) {
+        self.span(&mut block.span);
+        self.selectors(&mut block.condition);
+        self.contents(&mut block.content);
+    }
+
+    fn rule(&self, rule: &mut Rule) {
+        self.span(&mut rule.span);
+        self.contents(&mut rule.content);
+    }
+
+    fn contents(&self, contents: &mut Cow<'static, [RuleBlockContent]>) {
+        for content in contents.to_mut().iter_mut() {
+            match content {
+                RuleBlockContent::StyleAttr(ref mut m) => self.span(&mut m.span),
+                RuleBlockContent::Block(Bow::Boxed(ref mut m)) => self.block(m),
+                RuleBlockContent::Rule(Bow::Boxed(ref mut m)) => self.rule(m),
+                // Borrowed contents are not created by the parser.
+                RuleBlockContent::Block(Bow::Borrowed(_))
+                | RuleBlockContent::Rule(Bow::Borrowed(_)) => {}
+            }
+        }
+    }
+
+    fn scope_contents(&self, contents: &mut [ScopeContent]) {
+        for content in contents.iter_mut() {
+            match content {
+                ScopeContent::Block(ref mut m) => self.block(m),
+                ScopeContent::Rule(ref mut m) => self.rule(m),
+            }
+        }
+    }
+}
+
 /// Wrap a parser, tracing input and output.
 // if not cfg(test), this would trip up clippy.
 #[allow(clippy::let_and_return)]
@@ -158,19 +330,42 @@ impl Parser {
         traced_context("StyleAttrKey", Self::trimmed(Self::ident))(i)
     }
 
-    // TODO: Parse value properly.
-    fn style_attr_value(i: &str) -> IResult<&str, StringFragment, VerboseError<&str>> {
+    /// Parse the value of a style attribute, whether it is marked as `!important` and its
+    /// component values, unless it contains interpolations.
+    fn style_attr_value(i: &str) -> IResult<&str, StyleAttrValue, VerboseError<&str>> {
+        // Split `!important` from the value.
+        fn split_important(m: &str) -> (&str, bool) {
+            let m = m.trim();
+            match m.rsplit_once('!') {
+                Some((value, flag)) if flag.trim().eq_ignore_ascii_case("important") => {
+                    (value.trim_end(), true)
+                }
+                _ => (m, false),
+            }
+        }
+
         traced_context(
             "StyleAttrValue",
             Self::trimmed(map(
-                recognize(many1(alt((
-                    is_not("${;}/\""),
-                    recognize(Self::interpolation),
-                    Self::string,
-                    recognize(preceded(tag("/"), none_of("${;}\"*"))),
+                // Whether each part is an interpolation.
+                consumed(many1(alt((
+                    value(false, is_not("${;}/\"")),
+                    value(true, Self::interpolation),
+                    value(false, Self::string),
+                    value(false, preceded(tag("/"), none_of("${;}\"*"))),
                 )))),
-                |m: &str| StringFragment {
-                    inner: m.to_string().trim().to_string().into(),
+                |(m, interpolations): (&str, Vec<bool>)| {
+                    let (value, important) = split_important(m);
+                    StyleAttrValue {
+                        value: StringFragment {
+                            inner: value.to_string().into(),
+                        },
+                        important,
+                        component_values: match interpolations.contains(&true) {
+                            true => None,
+                            false => Some(ComponentValue::tokenize(value)),
+                        },
+                    }
                 },
             )),
         )(i)
@@ -181,16 +376,20 @@ impl Parser {
         traced_context(
             "StyleAttribute",
             Self::trimmed(expect_non_empty(map(
-                separated_pair(
+                spanned(separated_pair(
                     // Key
                     Self::style_attr_key,
                     // Separator
                     tag(":"),
-                    Self::style_attr_value,
-                ),
-                move |p: (&str, StringFragment)| StyleAttribute {
-                    key: p.0.trim().to_string().into(),
-                    value: vec![p.1].into(),
+                    // A value followed by `{` is the selector of a nested block, e.g.: `a:hover {`.
+                    terminated(Self::style_attr_value, not(tag("{"))),
+                )),
+                move |((key, value), span): ((&str, StyleAttrValue), Span)| StyleAttribute {
+                    key: key.trim().to_string().into(),
+                    value: vec![value.value].into(),
+                    important: value.important,
+                    component_values: value.component_values.map(Cow::from),
+                    span: Some(span),
                 },
             ))),
         )(i)
@@ -220,6 +419,11 @@ impl Parser {
     /// Parse a quoted string.
     // TODO: Parse ' quoted strings.
     fn string(i: &str) -> IResult<&str, &str, VerboseError<&str>> {
+        traced_context("String", Self::trimmed(Self::string_literal))(i)
+    }
+
+    /// Parse a quoted string without consuming surrounding whitespace.
+    fn string_literal(i: &str) -> IResult<&str, &str, VerboseError<&str>> {
         let escaped_char = traced_context("EscapedChar", recognize(preceded(tag("\\"), anychar)));
 
         let parse_str = recognize(preceded(
@@ -227,7 +431,7 @@ impl Parser {
             terminated(many0(alt((is_not(r#"\""#), escaped_char))), tag("\"")),
         ));
 
-        traced_context("String", Self::trimmed(expect_non_empty(parse_str)))(i)
+        traced_context("StringLiteral", expect_non_empty(parse_str))(i)
     }
 
     /// Parse a string interpolation.
@@ -246,19 +450,125 @@ impl Parser {
         )(i)
     }
 
+    /// Parse an interpolation in a selector.
+    fn selector_interpolation(i: &str) -> IResult<&str, &str, VerboseError<&str>> {
+        traced_context(
+            "SelectorInterpolation",
+            recognize(delimited(tag("${"), is_not("}"), tag("}"))),
+        )(i)
+    }
+
+    /// Parse the name of a simple selector, which may contain interpolations and escaped
+    /// characters.
+    fn selector_name(i: &str) -> IResult<&str, &str, VerboseError<&str>> {
+        traced_context(
+            "SelectorName",
+            recognize(many1(alt((
+                take_while1(|m: char| {
+                    m.is_ascii_alphanumeric() || "-_".contains(m) || !m.is_ascii()
+                }),
+                recognize(preceded(tag("\\"), anychar)),
+                Self::selector_interpolation,
+            )))),
+        )(i)
+    }
+
+    /// Parse the argument of a pseudo-class that is not a selector list.
+    fn pseudo_raw_argument(i: &str) -> IResult<&str, &str, VerboseError<&str>> {
+        traced_context(
+            "PseudoRawArgument",
+            recognize(many0(alt((
+                is_not("()\""),
+                Self::string,
+                recognize(delimited(tag("("), Self::pseudo_raw_argument, tag(")"))),
+            )))),
+        )(i)
+    }
+
+    /// Parse the name and the argument of a pseudo-class or a pseudo-element.
+    fn pseudo(i: &str) -> IResult<&str, Pseudo, VerboseError<&str>> {
+        let (i, name) = Self::selector_name(i)?;
+
+        let (i, argument) =
+            if PseudoArgument::SELECTOR_FUNCTIONS.contains(&name.to_ascii_lowercase().as_str()) {
+                opt(map(delimited(tag("("), Self::condition, tag(")")), |m| {
+                    PseudoArgument::Selectors(m.into())
+                }))(i)?
+            } else {
+                opt(map(
+                    delimited(tag("("), Self::pseudo_raw_argument, tag(")")),
+                    |m: &str| PseudoArgument::Raw(vec![m.trim().to_string().into()].into()),
+                ))(i)?
+            };
+
+        Ok((i, (vec![name.to_string().into()].into(), argument)))
+    }
+
+    /// Parse a simple selector.
+    fn simple_selector(i: &str) -> IResult<&str, SimpleSelector, VerboseError<&str>> {
+        fn frags(m: &str) -> Cow<'static, [StringFragment]> {
+            vec![m.to_string().into()].into()
+        }
+
+        traced_context(
+            "SimpleSelector",
+            alt((
+                map(tag("&"), |_| SimpleSelector::Nesting),
+                map(tag("*"), |m| SimpleSelector::Type(frags(m))),
+                map(preceded(tag("."), Self::selector_name), |m| {
+                    SimpleSelector::Class(frags(m))
+                }),
+                map(preceded(tag("#"), Self::selector_name), |m| {
+                    SimpleSelector::Id(frags(m))
+                }),
+                map(
+                    delimited(
+                        tag("["),
+                        recognize(many0(alt((is_not("]\""), Self::string)))),
+                        tag("]"),
+                    ),
+                    |m: &str| SimpleSelector::Attribute(frags(m.trim())),
+                ),
+                map(preceded(tag("::"), Self::pseudo), |(name, argument)| {
+                    SimpleSelector::PseudoElement { name, argument }
+                }),
+                map(preceded(tag(":"), Self::pseudo), |(name, argument)| {
+                    SimpleSelector::PseudoClass { name, argument }
+                }),
+                map(Self::selector_interpolation, |m| {
+                    SimpleSelector::Raw(frags(m))
+                }),
+                map(Self::string_literal, |m| SimpleSelector::Raw(frags(m))),
+                map(Self::selector_name, |m| SimpleSelector::Type(frags(m))),
+            )),
+        )(i)
+    }
+
     /// Parse a selector.
-    // TODO: Parse selector properly.
     fn selector(i: &str) -> IResult<&str, Selector, VerboseError<&str>> {
         traced_context(
             "Selector",
-            Self::trimmed(expect_non_empty(map(
-                recognize(many1(alt((
-                    recognize(preceded(none_of("$,}@{\""), opt(is_not("$,\"{")))),
-                    Self::string,
-                    recognize(Self::interpolation),
+            Self::trimmed(map_opt(
+                spanned(many1(alt((
+                    map(Self::simple_selector, SelectorToken::Simple),
+                    map(Self::trimmed(one_of(">+~")), |m| {
+                        SelectorToken::Combinator(match m {
+                            '>' => Combinator::Child,
+                            '+' => Combinator::NextSibling,
+                            _ => Combinator::SubsequentSibling,
+                        })
+                    }),
+                    map(verify(Self::sp, |m: &str| !m.is_empty()), |_| {
+                        SelectorToken::Whitespace
+                    }),
                 )))),
-                |p: &str| vec![p.trim().to_owned().into()].into(),
-            ))),
+                |(tokens, span)| {
+                    SelectorToken::into_selector(tokens).map(|m| Selector {
+                        span: Some(span),
+                        ..m
+                    })
+                },
+            )),
         )(i)
     }
 
@@ -283,11 +593,19 @@ impl Parser {
                         |i| Parser::attributes(i, false),
                         |m| m.into_iter().map(RuleBlockContent::StyleAttr).collect(),
                     ),
+                    // Or a layer statement
+                    map(Parser::layer_statement, |m| {
+                        vec![RuleBlockContent::Rule(Bow::Boxed(Box::new(m)))]
+                    }),
                     // Or an at rule
                     map(
                         |i| Parser::rule_block(i, RuleBlockKind::Other),
                         |m| vec![RuleBlockContent::Rule(Bow::Boxed(Box::new(m)))],
                     ),
+                    // Or a nested block
+                    map(Parser::block, |m| {
+                        vec![RuleBlockContent::Block(Bow::Boxed(Box::new(m)))]
+                    }),
                 ))),
                 |m: Vec<Vec<RuleBlockContent>>| m.into_iter().flatten().collect(),
             ))),
@@ -295,19 +613,21 @@ impl Parser {
     }
 
     /// Parse a [`Block`].
-    fn block(i: &str) -> IResult<&str, ScopeContent, VerboseError<&str>> {
+    fn block(i: &str) -> IResult<&str, Block, VerboseError<&str>> {
         traced_context(
             "Block",
             Self::trimmed(expect_non_empty(map(
-                pair(
+                spanned(pair(
                     Self::condition,
-                    delimited(tag("{"), Self::trimmed(Self::block_contents), tag("}")),
-                ),
-                |p: (Vec<Selector>, Vec<RuleBlockContent>)| {
-                    ScopeContent::Block(Block {
-                        condition: p.0.into(),
-                        content: p.1.into(),
-                    })
+                    preceded(
+                        tag("{"),
+                        cut(terminated(Self::trimmed(Self::block_contents), char('}'))),
+                    ),
+                )),
+                |(p, span): ((Vec<Selector>, Vec<RuleBlockContent>), Span)| Block {
+                    condition: p.0.into(),
+                    content: p.1.into(),
+                    span: Some(span),
                 },
             ))),
         )(i)
@@ -325,11 +645,19 @@ impl Parser {
                             m.into_iter().map(RuleBlockContent::StyleAttr).collect()
                         },
                     ),
+                    // Or a layer statement
+                    map(Parser::layer_statement, |m| {
+                        vec![RuleBlockContent::Rule(Bow::Boxed(Box::new(m)))]
+                    }),
                     // Or an at rule
                     map(
                         |i| Parser::rule_block(i, RuleBlockKind::Other),
                         |m: Rule| vec![RuleBlockContent::Rule(Bow::Boxed(Box::new(m)))],
                     ),
+                    // Or a nested block
+                    map(Parser::block, |m: Block| {
+                        vec![RuleBlockContent::Block(Bow::Boxed(Box::new(m)))]
+                    }),
                 )))),
             ),
             |m: Vec<Vec<RuleBlockContent>>| m.into_iter().flatten().collect(),
@@ -339,28 +667,39 @@ impl Parser {
     /// Parses a Rule Block
     fn rule_block(i: &str, kind: RuleBlockKind) -> IResult<&str, Rule, VerboseError<&str>> {
         let cond = |i| match kind {
-            RuleBlockKind::Other => {
-                Self::at_rule_condition(i, (tag("@container"), tag("@media"), tag("@supports")))
-            }
-            RuleBlockKind::Keyframes => map(recognize(Self::condition), |m| {
-                vec![m.trim().to_string().into()]
-            })(i),
+            RuleBlockKind::Other => Self::at_rule_condition(
+                i,
+                (
+                    tag("@container"),
+                    tag("@media"),
+                    tag("@supports"),
+                    tag("@layer"),
+                ),
+            ),
+            RuleBlockKind::Keyframes => map(
+                recognize(many1(alt((is_not("${};"), recognize(Self::interpolation))))),
+                |m: &str| vec![m.trim().to_string().into()],
+            )(i),
         };
 
         traced_context(
             "RuleBlock",
             Self::trimmed(expect_non_empty(map(
-                separated_pair(
+                spanned(separated_pair(
                     // Collect at Rules.
                     cond,
                     tag("{"),
                     // Collect contents with-in rules.
-                    terminated(Self::rule_block_contents, tag("}")),
-                ),
+                    cut(terminated(
+                        Self::trimmed(Self::rule_block_contents),
+                        char('}'),
+                    )),
+                )),
                 // Map Results into a scope
-                |p: (Vec<StringFragment>, Vec<RuleBlockContent>)| Rule {
+                |(p, span): ((Vec<StringFragment>, Vec<RuleBlockContent>), Span)| Rule {
                     condition: p.0.into(),
                     content: p.1.into(),
+                    span: Some(span),
                 },
             ))),
         )(i)
@@ -371,8 +710,8 @@ impl Parser {
         traced_context(
             "DanglingBlock",
             Self::trimmed(expect_non_empty(map(
-                |i| Self::attributes(i, true),
-                |attr: Vec<StyleAttribute>| {
+                spanned(|i| Self::attributes(i, true)),
+                |(attr, span): (Vec<StyleAttribute>, Span)| {
                     ScopeContent::Block(Block {
                         condition: Cow::Borrowed(&[]),
                         content: attr
@@ -380,6 +719,7 @@ impl Parser {
                             .map(|m| m.into())
                             .collect::<Vec<RuleBlockContent>>()
                             .into(),
+                        span: Some(span),
                     })
                 },
             ))),
@@ -431,103 +771,248 @@ impl Parser {
         traced_context(
             "Keyframes",
             Self::trimmed(map(
-                separated_pair(
+                spanned(separated_pair(
                     // Collect at Rules.
-                    |i| Self::at_rule_condition(i, (tag("@keyframes"), fail)),
+                    |i| Self::at_rule_condition(i, (tag("@keyframes"), tag("@-webkit-keyframes"))),
                     tag("{"),
                     // Collect contents with-in rules.
-                    terminated(
+                    cut(terminated(
                         many0(|i| Parser::rule_block(i, RuleBlockKind::Keyframes)),
-                        tag("}"),
-                    ),
-                ),
+                        char('}'),
+                    )),
+                )),
                 // Map Results into a scope
-                |p: (Vec<StringFragment>, Vec<Rule>)| Rule {
+                |(p, span): ((Vec<StringFragment>, Vec<Rule>), Span)| Rule {
                     condition: p.0.into(),
                     content: p
                         .1
                         .into_iter()
                         .map(|m| RuleBlockContent::Rule(Bow::Boxed(Box::new(m))))
                         .collect(),
+                    span: Some(span),
+                },
+            )),
+        )(i)
+    }
+
+    /// Parse `@font-face`
+    fn font_face(i: &str) -> IResult<&str, Rule, VerboseError<&str>> {
+        traced_context(
+            "FontFace",
+            Self::trimmed(map(
+                spanned(preceded(
+                    terminated(tag("@font-face"), Self::sp),
+                    preceded(
+                        tag("{"),
+                        cut(terminated(
+                            Self::trimmed(opt(|i| Self::attributes(i, false))),
+                            char('}'),
+                        )),
+                    ),
+                )),
+                |(attrs, span): (Option<Vec<StyleAttribute>>, Span)| Rule {
+                    condition: vec!["@font-face".into()].into(),
+                    content: attrs
+                        .unwrap_or_default()
+                        .into_iter()
+                        .map(RuleBlockContent::StyleAttr)
+                        .collect(),
+                    span: Some(span),
                 },
             )),
         )(i)
     }
 
-    /// Parse `@supports` and `@media`
+    /// Parse `@layer` statements, e.g.: `@layer base, components;`
+    fn layer_statement(i: &str) -> IResult<&str, Rule, VerboseError<&str>> {
+        traced_context(
+            "LayerStatement",
+            Self::trimmed(map(
+                spanned(terminated(
+                    pair(
+                        recognize(terminated(tag("@layer"), tag(" "))),
+                        Self::trimmed(is_not("${};")),
+                    ),
+                    tag(";"),
+                )),
+                |(p, span): ((&str, &str), Span)| Rule {
+                    condition: vec![p.0.to_string().into(), p.1.trim().to_string().into()].into(),
+                    content: Cow::Borrowed(&[]),
+                    span: Some(span),
+                },
+            )),
+        )(i)
+    }
+
+    /// Parse `@container`, `@supports`, `@media` and `@layer`
     fn at_rule(i: &str) -> IResult<&str, ScopeContent, VerboseError<&str>> {
         traced_context(
             "AtRule",
             Self::trimmed(expect_non_empty(map(
-                separated_pair(
+                spanned(separated_pair(
                     // Collect at Rules.
                     |i| {
                         Self::at_rule_condition(
                             i,
-                            (tag("@container"), tag("@supports"), tag("@media")),
+                            (
+                                tag("@container"),
+                                tag("@supports"),
+                                tag("@media"),
+                                tag("@layer"),
+                            ),
                         )
                     },
                     tag("{"),
                     // Collect contents with-in rules.
-                    terminated(Parser::scope_contents, tag("}")),
-                ),
+                    cut(terminated(Parser::scope_contents, char('}'))),
+                )),
                 // Map Results into a scope
-                |p: (Vec<StringFragment>, Vec<ScopeContent>)| {
+                |(p, span): ((Vec<StringFragment>, Vec<ScopeContent>), Span)| {
                     ScopeContent::Rule(Rule {
                         condition: p.0.into(),
-                        content: p
-                            .1
-                            .into_iter()
-                            .map(|m| match m {
-                                ScopeContent::Block(m) => {
-                                    RuleBlockContent::Block(Bow::Boxed(Box::new(m)))
-                                }
-                                ScopeContent::Rule(m) => {
-                                    RuleBlockContent::Rule(Bow::Boxed(Box::new(m)))
-                                }
-                            })
-                            .collect(),
+                        content: p.1.into_iter().map(RuleBlockContent::from).collect(),
+                        span: Some(span),
                     })
                 },
             ))),
         )(i)
     }
 
+    /// Parse a single item of a Scope
+    fn scope_content(i: &str) -> IResult<&str, ScopeContent, VerboseError<&str>> {
+        alt((
+            // Either a dangling block
+            Parser::dangling_block,
+            // Or a Block
+            map(Parser::block, ScopeContent::Block),
+            // @layer statements, these must come before at rules as they do not have a block.
+            map(Parser::layer_statement, ScopeContent::Rule),
+            // @container, @supports, @media and @layer
+            Parser::at_rule,
+            // @keyframes
+            map(Parser::keyframes, ScopeContent::Rule),
+            // @font-face
+            map(Parser::font_face, ScopeContent::Rule),
+        ))(i)
+    }
+
     /// Parse the Content of a Scope
     fn scope_contents(i: &str) -> IResult<&str, Vec<ScopeContent>, VerboseError<&str>> {
         traced_context(
             "ScopeContents",
-            Self::trimmed(expect_non_empty(many0(alt((
-                // Either a dangling block
-                Parser::dangling_block,
-                // Or a Block
-                Parser::block,
-                // @supports and @media
-                Parser::at_rule,
-                // @keyframes
-                map(Parser::keyframes, ScopeContent::Rule),
-            ))))),
+            Self::trimmed(expect_non_empty(many0(Parser::scope_content))),
         )(i)
     }
 
     /// Parse sheet
     /// A Scope can be either an at rule or a css scope.
-    fn sheet(i: &str) -> IResult<&str, Sheet, VerboseError<&str>> {
+    fn sheet(i: &str) -> IResult<&str, Vec<ScopeContent>, VerboseError<&str>> {
         traced_context(
             "StyleSheet",
             // Drop trailing whitespaces.
             Self::trimmed(map(many0(Self::scope), |p: Vec<Vec<ScopeContent>>| {
-                Sheet::from(p.into_iter().flatten().collect::<Vec<ScopeContent>>())
+                p.into_iter().flatten().collect::<Vec<ScopeContent>>()
             })),
         )(i)
     }
 
+    /// Locates the innermost error in the source, returns its offset and what was expected.
+    fn error_position(css: &str, e: &VerboseError<&str>) -> Option<(usize, Option<String>)> {
+        let (input, _) = e.errors.first()?;
+
+        // Prefer an expected character, otherwise use the innermost construct that is being
+        // parsed. Whitespaces and the stylesheet itself are tried everywhere and do not tell
+        // what went wrong.
+        let expected = e
+            .errors
+            .iter()
+            .find_map(|(_, kind)| match kind {
+                VerboseErrorKind::Char(c) => Some(format!("'{c}'")),
+                _ => None,
+            })
+            .or_else(|| {
+                e.errors.iter().find_map(|(_, kind)| match kind {
+                    VerboseErrorKind::Context(m)
+                        if !["Trimmed", "Whitespace", "StyleSheet"].contains(m) =>
+                    {
+                        Some(m.to_string())
+                    }
+                    _ => None,
+                })
+            });
+
+        Some((css.len() - input.len(), expected))
+    }
+
+    /// Replaces the statement that starts at the offset with whitespaces, so offsets after it
+    /// stay the same.
+    ///
+    /// Like browsers do, a statement ends after the next `;` or its block. If the end of the
+    /// enclosing block is reached first, the statement ends before it.
+    ///
+    /// Returns `false` if there is nothing to skip.
+    fn skip_statement(css: &mut String, offset: usize) -> bool {
+        let rest = &css[offset..];
+
+        let mut depth = 0;
+        let mut quote = None;
+        let mut end = rest.len();
+
+        let mut chars = rest.char_indices();
+        while let Some((index, c)) = chars.next() {
+            match (quote, c) {
+                (Some(_), '\\') => {
+                    chars.next();
+                }
+                (Some(q), c) if c == q => quote = None,
+                (Some(_), _) => {}
+                (None, '"' | '\'') => quote = Some(c),
+                (None, '{') => depth += 1,
+                (None, '}') if depth == 0 => {
+                    // A stray `}` is skipped by itself.
+                    end = if index == 0 { 1 } else { index };
+                    break;
+                }
+                (None, '}') => {
+                    depth -= 1;
+                    if depth == 0 {
+                        end = index + 1;
+                        break;
+                    }
+                }
+                (None, ';') if depth == 0 => {
+                    end = index + 1;
+                    break;
+                }
+                _ => {}
+            }
+        }
+
+        if rest[..end].trim().is_empty() {
+            return false;
+        }
+
+        // Line breaks are kept, so lines and columns stay the same as well.
+        let blank: String = rest[..end]
+            .chars()
+            .flat_map(|c| match c {
+                '\n' => std::iter::repeat_n('\n', 1),
+                c => std::iter::repeat_n(' ', c.len_utf8()),
+            })
+            .collect();
+        css.replace_range(offset..offset + end, &blank);
+
+        true
+    }
+
     /// The parse the style and returns a `Result<Sheet>`.
     pub fn parse(css: &str) -> Result<Sheet> {
         match Self::sheet(css) {
             // Converting to String, primarily due to lifetime requirements.
             Err(nom::Err::Error(e)) | Err(nom::Err::Failure(e)) => Err(Error::Parse {
                 reason: convert_error(css, e.clone()),
+                details: Self::error_position(css, &e)
+                    .map(|(offset, expected)| ParseErrorDetails::new(css, offset, expected)),
                 source: Some(VerboseError {
                     errors: e
                         .errors
@@ -539,20 +1024,112 @@ impl Parser {
             Err(nom::Err::Incomplete(e)) => Err(Error::Parse {
                 reason: format!("{e:#?}"),
                 source: None,
+                details: None,
             }),
-            Ok((_, res)) => Ok(res),
+            Ok((_, mut res)) => {
+                SpanResolver::new(css.len(), css.len()).scope_contents(&mut res);
+
+                Ok(Sheet::from(res))
+            }
+        }
+    }
+
+    /// Parses the style, skipping invalid parts instead of failing.
+    ///
+    /// Invalid declarations and blocks are skipped as [`skip_statement`](Self::skip_statement)
+    /// describes and blocks that are still open at the end of the input are closed.
+    ///
+    /// Returns the parsed sheet and the problems found. If the parser can not recover from a
+    /// problem, the rest of the style is skipped.
+    pub fn parse_lossy(css: &str) -> (Sheet, Vec<ParseErrorDetails>) {
+        let mut patched = css.to_string();
+        let mut contents = Vec::new();
+        let mut diagnostics = Vec::new();
+
+        // Top level statements are parsed one at a time, so only the statement that contains a
+        // problem is parsed again after it is skipped.
+        let mut start = 0;
+
+        loop {
+            let rest = match Self::sp(&patched[start..]) {
+                Ok((rest, _)) if !rest.is_empty() => rest,
+                _ => break,
+            };
+
+            let e = match Self::scope_content(rest) {
+                Ok((next, mut res)) if next.len() < rest.len() => {
+                    SpanResolver::new(patched.len(), css.len())
+                        .scope_contents(std::slice::from_mut(&mut res));
+                    contents.push(res);
+
+                    start = patched.len() - next.len();
+                    continue;
+                }
+                Ok(_) | Err(nom::Err::Incomplete(_)) => break,
+                Err(nom::Err::Error(e)) | Err(nom::Err::Failure(e)) => e,
+            };
+
+            let (offset, expected) = match Self::error_position(&patched, &e) {
+                Some(m) => m,
+                None => break,
+            };
+            diagnostics.push(ParseErrorDetails::new(css, offset.min(css.len()), expected));
+
+            if offset == patched.len() {
+                // Every `}` added closes a `{`, more of them can not help.
+                if patched.len() - css.len() >= css.matches('{').count() {
+                    break;
+                }
+                patched.push('}');
+            } else if !Self::skip_statement(&mut patched, offset) {
+                break;
+            }
         }
+
+        (Sheet::from(contents), diagnostics)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::ast::ComponentValue;
 
     fn init() {
         let _ = env_logger::builder().is_test(true).try_init();
     }
 
+    fn frags(s: &'static str) -> Cow<'static, [StringFragment]> {
+        vec![s.into()].into()
+    }
+
+    fn class(s: &'static str) -> SimpleSelector {
+        SimpleSelector::Class(frags(s))
+    }
+
+    fn ty(s: &'static str) -> SimpleSelector {
+        SimpleSelector::Type(frags(s))
+    }
+
+    fn pseudo_class(s: &'static str) -> SimpleSelector {
+        SimpleSelector::PseudoClass {
+            name: frags(s),
+            argument: None,
+        }
+    }
+
+    fn descendant(selectors: Vec<SimpleSelector>) -> CompoundSelector {
+        CompoundSelector {
+            combinator: Some(Combinator::Descendant),
+            selectors: selectors.into(),
+        }
+    }
+
+    /// Creates a selector with a single compound selector.
+    fn sel(selectors: Vec<SimpleSelector>) -> Selector {
+        vec![CompoundSelector::from(selectors)].into()
+    }
+
     #[test]
     fn test_whitespace() {
         init();
@@ -592,25 +1169,36 @@ mod tests {
                 content: vec![StyleAttribute {
                     key: "background-color".into(),
                     value: vec!["red".into()].into(),
+                    important: false,
+                    component_values: None,
+                    span: None,
                 }
                 .into()]
                 .into(),
+                span: None,
             }),
             ScopeContent::Block(Block {
-                condition: vec![vec![".nested".into()].into()].into(),
+                condition: vec![sel(vec![class("nested")])].into(),
                 content: vec![
                     StyleAttribute {
                         key: "background-color".into(),
                         value: vec!["blue".into()].into(),
+                        important: false,
+                        component_values: None,
+                        span: None,
                     }
                     .into(),
                     StyleAttribute {
                         key: "width".into(),
                         value: vec!["100px".into()].into(),
+                        important: false,
+                        component_values: None,
+                        span: None,
                     }
                     .into(),
                 ]
                 .into(),
+                span: None,
             }),
         ]);
         assert_eq!(parsed, expected);
@@ -637,32 +1225,48 @@ mod tests {
                     StyleAttribute {
                         key: "background-color".into(),
                         value: vec!["red".into()].into(),
+                        important: false,
+                        component_values: None,
+                        span: None,
                     }
                     .into(),
                     StyleAttribute {
                         key: "content".into(),
                         value: vec![r#"";""#.into()].into(),
+                        important: false,
+                        component_values: None,
+                        span: None,
                     }
                     .into(),
                 ]
                 .into(),
+                span: None,
             }),
             ScopeContent::Block(Block {
-                condition: vec![vec![r#"[placeholder="someone@example.com"]"#.into()].into()]
-                    .into(),
+                condition: vec![sel(vec![SimpleSelector::Attribute(frags(
+                    r#"placeholder="someone@example.com""#,
+                ))])]
+                .into(),
                 content: vec![
                     StyleAttribute {
                         key: "background-color".into(),
                         value: vec!["blue".into()].into(),
+                        important: false,
+                        component_values: None,
+                        span: None,
                     }
                     .into(),
                     StyleAttribute {
                         key: "width".into(),
                         value: vec!["100px".into()].into(),
+                        important: false,
+                        component_values: None,
+                        span: None,
                     }
                     .into(),
                 ]
                 .into(),
+                span: None,
             }),
         ]);
         assert_eq!(parsed, expected);
@@ -680,20 +1284,30 @@ mod tests {
         let parsed = Parser::parse(test_str).expect("Failed to Parse Style");
 
         let expected = Sheet::from(vec![ScopeContent::Block(Block {
-            condition: vec![vec![r#"[placeholder="\" {}"]"#.into()].into()].into(),
+            condition: vec![sel(vec![SimpleSelector::Attribute(frags(
+                r#"placeholder="\" {}""#,
+            ))])]
+            .into(),
             content: vec![
                 StyleAttribute {
                     key: "background-color".into(),
                     value: vec!["blue".into()].into(),
+                    important: false,
+                    component_values: None,
+                    span: None,
                 }
                 .into(),
                 StyleAttribute {
                     key: "width".into(),
                     value: vec!["100px".into()].into(),
+                    important: false,
+                    component_values: None,
+                    span: None,
                 }
                 .into(),
             ]
             .into(),
+            span: None,
         })]);
         assert_eq!(parsed, expected);
     }
@@ -708,13 +1322,17 @@ mod tests {
         let parsed = Parser::parse(test_str).expect("Failed to Parse Style");
 
         let expected = Sheet::from(vec![ScopeContent::Block(Block {
-            condition: vec![vec!["&:hover".into()].into()].into(),
+            condition: vec![sel(vec![SimpleSelector::Nesting, pseudo_class("hover")])].into(),
             content: vec![StyleAttribute {
                 key: "background-color".into(),
                 value: vec!["#d0d0d9".into()].into(),
+                important: false,
+                component_values: None,
+                span: None,
             }
             .into()]
             .into(),
+            span: None,
         })]);
         assert_eq!(parsed, expected);
     }
@@ -744,13 +1362,18 @@ mod tests {
                         content: vec![StyleAttribute {
                             key: "background-color".into(),
                             value: vec!["red".into()].into(),
+                            important: false,
+                            component_values: None,
+                            span: None,
                         }
                         .into()]
                         .into(),
+                        span: None,
                     }
                     .into(),
                 )]
                 .into(),
+                span: None,
             }),
             ScopeContent::Rule(Rule {
                 condition: vec!["@media ".into(), "screen and (max-width: 200px)".into()].into(),
@@ -760,13 +1383,18 @@ mod tests {
                         content: vec![StyleAttribute {
                             key: "color".into(),
                             value: vec!["yellow".into()].into(),
+                            important: false,
+                            component_values: None,
+                            span: None,
                         }
                         .into()]
                         .into(),
+                        span: None,
                     }
                     .into(),
                 )]
                 .into(),
+                span: None,
             }),
         ]);
 
@@ -800,22 +1428,31 @@ mod tests {
                         content: vec![StyleAttribute {
                             key: "background-color".into(),
                             value: vec!["red".into()].into(),
+                            important: false,
+                            component_values: None,
+                            span: None,
                         }
                         .into()]
                         .into(),
+                        span: None,
                     }
                     .into(),
                 )]
                 .into(),
+                span: None,
             }),
             ScopeContent::Block(Block {
-                condition: vec![vec![".some-class2".into()].into()].into(),
+                condition: vec![sel(vec![class("some-class2")])].into(),
                 content: vec![StyleAttribute {
                     key: "color".into(),
                     value: vec!["yellow".into()].into(),
+                    important: false,
+                    component_values: None,
+                    span: None,
                 }
                 .into()]
                 .into(),
+                span: None,
             }),
         ]);
 
@@ -842,22 +1479,37 @@ mod tests {
 
         let expected = Sheet::from(vec![
             ScopeContent::Block(Block {
-                condition: vec![vec!["div".into()].into(), vec!["span".into()].into()].into(),
+                condition: vec![sel(vec![ty("div")]), sel(vec![ty("span")])].into(),
                 content: vec![StyleAttribute {
                     key: "color".into(),
                     value: vec!["yellow".into()].into(),
+                    important: false,
+                    component_values: None,
+                    span: None,
                 }
                 .into()]
                 .into(),
+                span: None,
             }),
             ScopeContent::Block(Block {
-                condition: vec![vec!["&".into()].into(), vec!["& input".into()].into()].into(),
+                condition: vec![
+                    sel(vec![SimpleSelector::Nesting]),
+                    Selector::from(vec![
+                        vec![SimpleSelector::Nesting].into(),
+                        descendant(vec![ty("input")]),
+                    ]),
+                ]
+                .into(),
                 content: vec![StyleAttribute {
                     key: "color".into(),
                     value: vec!["pink".into()].into(),
+                    important: false,
+                    component_values: None,
+                    span: None,
                 }
                 .into()]
                 .into(),
+                span: None,
             }),
         ]);
 
@@ -887,13 +1539,18 @@ mod tests {
                     content: vec![StyleAttribute {
                         key: "background".into(),
                         value: vec!["#000".into()].into(),
+                        important: false,
+                        component_values: None,
+                        span: None,
                     }
                     .into()]
                     .into(),
+                    span: None,
                 }
                 .into(),
             )]
             .into(),
+            span: None,
         })]);
 
         assert_eq!(parsed, expected);
@@ -933,24 +1590,35 @@ mod tests {
                             StyleAttribute {
                                 key: "backdrop-filter".into(),
                                 value: vec!["blur(2px)".into()].into(),
+                                important: false,
+                                component_values: None,
+                                span: None,
                             }
                             .into(),
                             StyleAttribute {
                                 key: "-webkit-backdrop-filter".into(),
                                 value: vec!["blur(2px)".into()].into(),
+                                important: false,
+                                component_values: None,
+                                span: None,
                             }
                             .into(),
                             StyleAttribute {
                                 key: "background-color".into(),
                                 value: vec!["rgb(0, 0, 0, 0.7)".into()].into(),
+                                important: false,
+                                component_values: None,
+                                span: None,
                             }
                             .into(),
                         ]
                         .into(),
+                        span: None,
                     }
                     .into(),
                 )]
                 .into(),
+                span: None,
             }),
             ScopeContent::Rule(Rule {
                 condition: vec![
@@ -965,13 +1633,18 @@ mod tests {
                         content: vec![StyleAttribute {
                             key: "background-color".into(),
                             value: vec!["rgb(25, 25, 25)".into()].into(),
+                            important: false,
+                            component_values: None,
+                            span: None,
                         }
                         .into()]
                         .into(),
+                        span: None,
                     }
                     .into(),
                 )]
                 .into(),
+                span: None,
             }),
         ]);
 
@@ -985,14 +1658,65 @@ mod tests {
         init();
         assert_eq!(
             Parser::selector("&").map(|m| m.1),
-            Ok(vec!["&".into()].into())
+            Ok(sel(vec![SimpleSelector::Nesting]))
         );
         assert_eq!(
             Parser::selector("& input").map(|m| m.1),
-            Ok(vec!["& input".into()].into())
+            Ok(Selector::from(vec![
+                vec![SimpleSelector::Nesting].into(),
+                descendant(vec![ty("input")]),
+            ]))
         );
     }
 
+    #[test]
+    fn test_selector_structure() {
+        init();
+        assert_eq!(
+            Parser::selector(r#"a[href*="&"] > .b:not(&.c)::before ~ li:nth-child(2n + 1)"#)
+                .map(|m| m.1),
+            Ok(Selector::from(vec![
+                vec![ty("a"), SimpleSelector::Attribute(frags(r#"href*="&""#)),].into(),
+                CompoundSelector {
+                    combinator: Some(Combinator::Child),
+                    selectors: vec![
+                        class("b"),
+                        SimpleSelector::PseudoClass {
+                            name: frags("not"),
+                            argument: Some(PseudoArgument::Selectors(
+                                vec![sel(vec![SimpleSelector::Nesting, class("c")])].into(),
+                            )),
+                        },
+                        SimpleSelector::PseudoElement {
+                            name: frags("before"),
+                            argument: None,
+                        },
+                    ]
+                    .into(),
+                },
+                CompoundSelector {
+                    combinator: Some(Combinator::SubsequentSibling),
+                    selectors: vec![
+                        ty("li"),
+                        SimpleSelector::PseudoClass {
+                            name: frags("nth-child"),
+                            argument: Some(PseudoArgument::Raw(frags("2n + 1"))),
+                        },
+                    ]
+                    .into(),
+                },
+            ]))
+        );
+        assert_eq!(
+            Parser::selector("> #id.btn-${size}").map(|m| m.1),
+            Ok(Selector::from(vec![CompoundSelector {
+                combinator: Some(Combinator::Child),
+                selectors: vec![SimpleSelector::Id(frags("id")), class("btn-${size}"),].into(),
+            }]))
+        );
+        assert!(Parser::selector("a >").is_err());
+    }
+
     #[test]
     fn test_interpolation() {
         init();
@@ -1011,29 +1735,40 @@ mod tests {
                 content: vec![StyleAttribute {
                     key: "background-color".into(),
                     value: vec!["red".into()].into(),
+                    important: false,
+                    component_values: None,
+                    span: None,
                 }
                 .into()]
                 .into(),
+                span: None,
             }),
             ScopeContent::Block(Block {
                 condition: vec![
-                    vec![".nested".into()].into(),
-                    vec!["${var_a}".into()].into(),
+                    sel(vec![class("nested")]),
+                    sel(vec![SimpleSelector::Raw(frags("${var_a}"))]),
                 ]
                 .into(),
                 content: vec![
                     StyleAttribute {
                         key: "background-color".into(),
                         value: vec!["blue".into()].into(),
+                        important: false,
+                        component_values: None,
+                        span: None,
                     }
                     .into(),
                     StyleAttribute {
                         key: "width".into(),
                         value: vec!["100px".into()].into(),
+                        important: false,
+                        component_values: None,
+                        span: None,
                     }
                     .into(),
                 ]
                 .into(),
+                span: None,
             }),
         ]);
         assert_eq!(parsed, expected);
@@ -1046,8 +1781,9 @@ mod tests {
         let parsed = Parser::parse(test_str).expect("Failed to Parse Style");
 
         let expected = Sheet::from(vec![ScopeContent::Block(Block {
-            condition: vec![vec![".nested".into()].into()].into(),
+            condition: vec![sel(vec![class("nested")])].into(),
             content: vec![].into(),
+            span: None,
         })]);
         assert_eq!(parsed, expected);
     }
@@ -1067,26 +1803,35 @@ mod tests {
                         content: vec![StyleAttribute {
                             key: "color".into(),
                             value: vec!["black".into()].into(),
+                            important: false,
+                            component_values: None,
+                            span: None,
                         }
                         .into()]
                         .into(),
+                        span: None,
                     }
                     .into(),
                 ),
                 RuleBlockContent::Block(
                     Block {
-                        condition: vec![vec![".nested".into()].into()].into(),
+                        condition: vec![sel(vec![class("nested")])].into(),
                         content: vec![StyleAttribute {
                             key: "cursor".into(),
                             value: vec!["none".into()].into(),
+                            important: false,
+                            component_values: None,
+                            span: None,
                         }
                         .into()]
                         .into(),
+                        span: None,
                     }
                     .into(),
                 ),
             ]
             .into(),
+            span: None,
         })]);
         assert_eq!(parsed, expected);
     }
@@ -1108,25 +1853,38 @@ mod tests {
                 StyleAttribute {
                     key: "position".into(),
                     value: vec!["fixed".into()].into(),
+                    important: false,
+                    component_values: None,
+                    span: None,
                 }
                 .into(),
                 StyleAttribute {
                     key: "z-index".into(),
                     value: vec!["1".into()].into(),
+                    important: false,
+                    component_values: None,
+                    span: None,
                 }
                 .into(),
                 StyleAttribute {
                     key: "width".into(),
                     value: vec!["100%".into()].into(),
+                    important: false,
+                    component_values: None,
+                    span: None,
                 }
                 .into(),
                 StyleAttribute {
                     key: "height".into(),
                     value: vec!["100%".into()].into(),
+                    important: false,
+                    component_values: None,
+                    span: None,
                 }
                 .into(),
             ]
             .into(),
+            span: None,
         })]);
         assert_eq!(parsed, expected);
     }
@@ -1140,6 +1898,7 @@ mod tests {
         let expected = Sheet::from(vec![ScopeContent::Rule(Rule {
             condition: vec!["@media ".into(), "screen and (max-width: 500px)".into()].into(),
             content: vec![].into(),
+            span: None,
         })]);
         assert_eq!(parsed, expected);
     }
@@ -1180,28 +1939,49 @@ mod tests {
                 content: vec![StyleAttribute {
                     key: "color".into(),
                     value: vec!["${color}".into()].into(),
+                    important: false,
+                    component_values: None,
+                    span: None,
                 }
                 .into()]
                 .into(),
+                span: None,
             }),
             ScopeContent::Block(Block {
-                condition: vec![vec!["span".into()].into(), vec!["${sel_div}".into()].into()]
-                    .into(),
+                condition: vec![
+                    sel(vec![ty("span")]),
+                    sel(vec![SimpleSelector::Raw(frags("${sel_div}"))]),
+                ]
+                .into(),
                 content: vec![StyleAttribute {
                     key: "background-color".into(),
                     value: vec!["blue".into()].into(),
+                    important: false,
+                    component_values: None,
+                    span: None,
                 }
                 .into()]
                 .into(),
+                span: None,
             }),
             ScopeContent::Block(Block {
-                condition: vec![vec![":not(${sel_root})".into()].into()].into(),
+                condition: vec![sel(vec![SimpleSelector::PseudoClass {
+                    name: frags("not"),
+                    argument: Some(PseudoArgument::Selectors(
+                        vec![sel(vec![SimpleSelector::Raw(frags("${sel_root}"))])].into(),
+                    )),
+                }])]
+                .into(),
                 content: vec![StyleAttribute {
                     key: "background-color".into(),
                     value: vec!["black".into()].into(),
+                    important: false,
+                    component_values: None,
+                    span: None,
                 }
                 .into()]
                 .into(),
+                span: None,
             }),
             ScopeContent::Rule(Rule {
                 condition: vec!["@media ".into(), "screen and ${breakpoint}".into()].into(),
@@ -1211,13 +1991,18 @@ mod tests {
                         content: vec![StyleAttribute {
                             key: "display".into(),
                             value: vec!["flex".into()].into(),
+                            important: false,
+                            component_values: None,
+                            span: None,
                         }
                         .into()]
                         .into(),
+                        span: None,
                     }
                     .into(),
                 )]
                 .into(),
+                span: None,
             }),
         ]);
 
@@ -1255,28 +2040,49 @@ mod tests {
                 content: vec![StyleAttribute {
                     key: "color".into(),
                     value: vec!["${color}".into()].into(),
+                    important: false,
+                    component_values: None,
+                    span: None,
                 }
                 .into()]
                 .into(),
+                span: None,
             }),
             ScopeContent::Block(Block {
-                condition: vec![vec!["span".into()].into(), vec!["${sel_div}".into()].into()]
-                    .into(),
+                condition: vec![
+                    sel(vec![ty("span")]),
+                    sel(vec![SimpleSelector::Raw(frags("${sel_div}"))]),
+                ]
+                .into(),
                 content: vec![StyleAttribute {
                     key: "background-color".into(),
                     value: vec!["blue".into()].into(),
+                    important: false,
+                    component_values: None,
+                    span: None,
                 }
                 .into()]
                 .into(),
+                span: None,
             }),
             ScopeContent::Block(Block {
-                condition: vec![vec![":not(${sel_root})".into()].into()].into(),
+                condition: vec![sel(vec![SimpleSelector::PseudoClass {
+                    name: frags("not"),
+                    argument: Some(PseudoArgument::Selectors(
+                        vec![sel(vec![SimpleSelector::Raw(frags("${sel_root}"))])].into(),
+                    )),
+                }])]
+                .into(),
                 content: vec![StyleAttribute {
                     key: "background-color".into(),
                     value: vec!["black".into()].into(),
+                    important: false,
+                    component_values: None,
+                    span: None,
                 }
                 .into()]
                 .into(),
+                span: None,
             }),
             ScopeContent::Rule(Rule {
                 condition: vec!["@media ".into(), "screen and ${breakpoint}".into()].into(),
@@ -1286,13 +2092,18 @@ mod tests {
                         content: vec![StyleAttribute {
                             key: "display".into(),
                             value: vec!["flex".into()].into(),
+                            important: false,
+                            component_values: None,
+                            span: None,
                         }
                         .into()]
                         .into(),
+                        span: None,
                     }
                     .into(),
                 )]
                 .into(),
+                span: None,
             }),
         ]);
 
@@ -1325,28 +2136,49 @@ mod tests {
                 content: vec![StyleAttribute {
                     key: "color".into(),
                     value: vec!["\"$${color}\"".into()].into(),
+                    important: false,
+                    component_values: None,
+                    span: None,
                 }
                 .into()]
                 .into(),
+                span: None,
             }),
             ScopeContent::Block(Block {
-                condition: vec![vec!["span".into()].into(), vec!["${sel_div}".into()].into()]
-                    .into(),
+                condition: vec![
+                    sel(vec![ty("span")]),
+                    sel(vec![SimpleSelector::Raw(frags("${sel_div}"))]),
+                ]
+                .into(),
                 content: vec![StyleAttribute {
                     key: "background-color".into(),
                     value: vec!["blue".into()].into(),
+                    important: false,
+                    component_values: None,
+                    span: None,
                 }
                 .into()]
                 .into(),
+                span: None,
             }),
             ScopeContent::Block(Block {
-                condition: vec![vec![":not(${sel_root})".into()].into()].into(),
+                condition: vec![sel(vec![SimpleSelector::PseudoClass {
+                    name: frags("not"),
+                    argument: Some(PseudoArgument::Selectors(
+                        vec![sel(vec![SimpleSelector::Raw(frags("${sel_root}"))])].into(),
+                    )),
+                }])]
+                .into(),
                 content: vec![StyleAttribute {
                     key: "background-color".into(),
                     value: vec!["black".into()].into(),
+                    important: false,
+                    component_values: None,
+                    span: None,
                 }
                 .into()]
                 .into(),
+                span: None,
             }),
             ScopeContent::Rule(Rule {
                 condition: vec!["@media ".into(), "screen and ${breakpoint}".into()].into(),
@@ -1356,13 +2188,18 @@ mod tests {
                         content: vec![StyleAttribute {
                             key: "display".into(),
                             value: vec!["flex".into()].into(),
+                            important: false,
+                            component_values: None,
+                            span: None,
                         }
                         .into()]
                         .into(),
+                        span: None,
                     }
                     .into(),
                 )]
                 .into(),
+                span: None,
             }),
         ]);
 
@@ -1395,7 +2232,7 @@ mod tests {
 
         let expected = Sheet::from(vec![
             ScopeContent::Block(Block {
-                condition: vec![vec!["span".into()].into()].into(),
+                condition: vec![sel(vec![ty("span")])].into(),
                 content: vec![RuleBlockContent::Rule(
                     Rule {
                         condition: vec!["@media ".into(), "screen and (max-width: 500px)".into()]
@@ -1403,15 +2240,20 @@ mod tests {
                         content: vec![RuleBlockContent::StyleAttr(StyleAttribute {
                             key: "background-color".into(),
                             value: vec!["blue".into()].into(),
+                            important: false,
+                            component_values: None,
+                            span: None,
                         })]
                         .into(),
+                        span: None,
                     }
                     .into(),
                 )]
                 .into(),
+                span: None,
             }),
             ScopeContent::Block(Block {
-                condition: vec![vec!["div".into()].into()].into(),
+                condition: vec![sel(vec![ty("div")])].into(),
                 content: vec![RuleBlockContent::Rule(
                     Rule {
                         condition: vec!["@supports ".into(), "(max-width: 500px)".into()].into(),
@@ -1425,16 +2267,22 @@ mod tests {
                                 content: vec![RuleBlockContent::StyleAttr(StyleAttribute {
                                     key: "background-color".into(),
                                     value: vec!["blue".into()].into(),
+                                    important: false,
+                                    component_values: None,
+                                    span: None,
                                 })]
                                 .into(),
+                                span: None,
                             }
                             .into(),
                         )]
                         .into(),
+                        span: None,
                     }
                     .into(),
                 )]
                 .into(),
+                span: None,
             }),
             ScopeContent::Rule(Rule {
                 condition: vec!["@media ".into(), "screen and ${breakpoint}".into()].into(),
@@ -1444,13 +2292,219 @@ mod tests {
                         content: vec![StyleAttribute {
                             key: "display".into(),
                             value: vec!["flex".into()].into(),
+                            important: false,
+                            component_values: None,
+                            span: None,
                         }
                         .into()]
                         .into(),
+                        span: None,
                     }
                     .into(),
                 )]
                 .into(),
+                span: None,
+            }),
+        ]);
+
+        assert_eq!(parsed, expected);
+    }
+
+    #[test]
+    fn test_nested_block() {
+        init();
+        let test_str = r#"
+            .card {
+                color: red;
+                .title {
+                    font-weight: bold;
+                }
+                a:hover {
+                    color: blue;
+                }
+                @media print {
+                    &:hover {
+                        color: black;
+                    }
+                }
+            }
+        "#;
+
+        let parsed = Parser::parse(test_str).expect("Failed to Parse Style");
+
+        let expected = Sheet::from(vec![ScopeContent::Block(Block {
+            condition: vec![sel(vec![class("card")])].into(),
+            content: vec![
+                StyleAttribute {
+                    key: "color".into(),
+                    value: vec!["red".into()].into(),
+                    important: false,
+                    component_values: None,
+                    span: None,
+                }
+                .into(),
+                RuleBlockContent::Block(
+                    Block {
+                        condition: vec![sel(vec![class("title")])].into(),
+                        content: vec![StyleAttribute {
+                            key: "font-weight".into(),
+                            value: vec!["bold".into()].into(),
+                            important: false,
+                            component_values: None,
+                            span: None,
+                        }
+                        .into()]
+                        .into(),
+                        span: None,
+                    }
+                    .into(),
+                ),
+                RuleBlockContent::Block(
+                    Block {
+                        condition: vec![sel(vec![ty("a"), pseudo_class("hover")])].into(),
+                        content: vec![StyleAttribute {
+                            key: "color".into(),
+                            value: vec!["blue".into()].into(),
+                            important: false,
+                            component_values: None,
+                            span: None,
+                        }
+                        .into()]
+                        .into(),
+                        span: None,
+                    }
+                    .into(),
+                ),
+                RuleBlockContent::Rule(
+                    Rule {
+                        condition: vec!["@media ".into(), "print".into()].into(),
+                        content: vec![RuleBlockContent::Block(
+                            Block {
+                                condition: vec![sel(vec![
+                                    SimpleSelector::Nesting,
+                                    pseudo_class("hover"),
+                                ])]
+                                .into(),
+                                content: vec![StyleAttribute {
+                                    key: "color".into(),
+                                    value: vec!["black".into()].into(),
+                                    important: false,
+                                    component_values: None,
+                                    span: None,
+                                }
+                                .into()]
+                                .into(),
+                                span: None,
+                            }
+                            .into(),
+                        )]
+                        .into(),
+                        span: None,
+                    }
+                    .into(),
+                ),
+            ]
+            .into(),
+            span: None,
+        })]);
+
+        assert_eq!(parsed, expected);
+    }
+
+    #[test]
+    fn test_font_face() {
+        init();
+        let test_str = r#"
+            @font-face {
+                font-family: "Open Sans";
+                src: url("/fonts/OpenSans-Regular.woff2") format("woff2"),
+                    url(https://example.com/OpenSans-Regular.woff) format("woff");
+            }
+
+            @font-face {}
+        "#;
+
+        let parsed = Parser::parse(test_str).expect("Failed to Parse Style");
+
+        let expected = Sheet::from(vec![
+            ScopeContent::Rule(Rule {
+                condition: vec!["@font-face".into()].into(),
+                content: vec![
+                    RuleBlockContent::StyleAttr(StyleAttribute {
+                        key: "font-family".into(),
+                        value: vec![r#""Open Sans""#.into()].into(),
+                        important: false,
+                        component_values: None,
+                        span: None,
+                    }),
+                    RuleBlockContent::StyleAttr(StyleAttribute {
+                        key: "src".into(),
+                        value: vec![r#"url("/fonts/OpenSans-Regular.woff2") format("woff2"),
+                    url(https://example.com/OpenSans-Regular.woff) format("woff")"#
+                            .into()]
+                        .into(),
+                        important: false,
+                        component_values: None,
+                        span: None,
+                    }),
+                ]
+                .into(),
+                span: None,
+            }),
+            ScopeContent::Rule(Rule {
+                condition: vec!["@font-face".into()].into(),
+                content: vec![].into(),
+                span: None,
+            }),
+        ]);
+
+        assert_eq!(parsed, expected);
+    }
+
+    #[test]
+    fn test_layer() {
+        init();
+        let test_str = r#"
+            @layer base, components;
+            @layer base {
+                .title {
+                    @layer components;
+                    color: red;
+                }
+            }
+        "#;
+
+        let parsed = Parser::parse(test_str).expect("Failed to Parse Style");
+
+        let expected = Sheet::from(vec![
+            ScopeContent::Rule(Rule {
+                condition: vec!["@layer ".into(), "base, components".into()].into(),
+                content: vec![].into(),
+                span: None,
+            }),
+            ScopeContent::Rule(Rule {
+                condition: vec!["@layer ".into(), "base".into()].into(),
+                content: vec![RuleBlockContent::Block(Bow::Boxed(Box::new(Block {
+                    condition: vec![sel(vec![class("title")])].into(),
+                    content: vec![
+                        RuleBlockContent::Rule(Bow::Boxed(Box::new(Rule {
+                            condition: vec!["@layer ".into(), "components".into()].into(),
+                            content: vec![].into(),
+                            span: None,
+                        }))),
+                        RuleBlockContent::StyleAttr(StyleAttribute {
+                            key: "color".into(),
+                            value: vec!["red".into()].into(),
+                            important: false,
+                            component_values: None,
+                            span: None,
+                        }),
+                    ]
+                    .into(),
+                    span: None,
+                })))]
+                .into(),
+                span: None,
             }),
         ]);
 
@@ -1474,21 +2528,34 @@ mod tests {
                 RuleBlockContent::StyleAttr(StyleAttribute {
                     key: "grid-row".into(),
                     value: vec!["1 / 3".into()].into(),
+                    important: false,
+                    component_values: None,
+                    span: None,
                 }),
                 RuleBlockContent::StyleAttr(StyleAttribute {
                     key: "grid-row".into(),
                     value: vec!["1/3".into()].into(),
+                    important: false,
+                    component_values: None,
+                    span: None,
                 }),
                 RuleBlockContent::StyleAttr(StyleAttribute {
                     key: "grid-row".into(),
                     value: vec!["1/ 3".into()].into(),
+                    important: false,
+                    component_values: None,
+                    span: None,
                 }),
                 RuleBlockContent::StyleAttr(StyleAttribute {
                     key: "grid-row".into(),
                     value: vec!["1 /3".into()].into(),
+                    important: false,
+                    component_values: None,
+                    span: None,
                 }),
             ]
             .into(),
+            span: None,
         })]);
 
         assert_eq!(parsed, expected);
@@ -1507,10 +2574,251 @@ mod tests {
             content: vec![RuleBlockContent::StyleAttr(StyleAttribute {
                 key: "background-image".into(),
                 value: vec!["url(https://example.com/example.jpg)".into()].into(),
+                important: false,
+                component_values: None,
+                span: None,
             })]
             .into(),
+            span: None,
         })]);
 
         assert_eq!(parsed, expected);
     }
+
+    #[test]
+    fn test_important() {
+        let test_str = r#"
+            color: red !important;
+            margin: 0 auto ! IMPORTANT;
+            content: "!important";
+        "#;
+
+        let parsed = Parser::parse(test_str).expect("Failed to Parse Style");
+
+        let expected = Sheet::from(vec![ScopeContent::Block(Block {
+            condition: vec![].into(),
+            content: vec![
+                RuleBlockContent::StyleAttr(StyleAttribute {
+                    key: "color".into(),
+                    value: vec!["red".into()].into(),
+                    important: true,
+                    component_values: None,
+                    span: None,
+                }),
+                RuleBlockContent::StyleAttr(StyleAttribute {
+                    key: "margin".into(),
+                    value: vec!["0 auto".into()].into(),
+                    important: true,
+                    component_values: None,
+                    span: None,
+                }),
+                RuleBlockContent::StyleAttr(StyleAttribute {
+                    key: "content".into(),
+                    value: vec![r#""!important""#.into()].into(),
+                    important: false,
+                    component_values: None,
+                    span: None,
+                }),
+            ]
+            .into(),
+            span: None,
+        })]);
+
+        assert_eq!(parsed, expected);
+
+        let margin = match parsed[0] {
+            ScopeContent::Block(ref m) => match m.content[1] {
+                RuleBlockContent::StyleAttr(ref m) => m.component_values(),
+                _ => panic!("expected a style attribute"),
+            },
+            _ => panic!("expected a block"),
+        };
+        assert_eq!(
+            margin,
+            vec![
+                ComponentValue::Number("0".into()),
+                ComponentValue::Whitespace,
+                ComponentValue::Ident("auto".into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_component_values() {
+        let parsed =
+            Parser::parse("width: calc(100% - 1px); height: ${h};").expect("Failed to Parse Style");
+
+        let attrs = match parsed[0] {
+            ScopeContent::Block(ref m) => m
+                .content
+                .iter()
+                .map(|m| match m {
+                    RuleBlockContent::StyleAttr(ref m) => m,
+                    _ => panic!("expected a style attribute"),
+                })
+                .collect::<Vec<_>>(),
+            _ => panic!("expected a block"),
+        };
+
+        // Values without interpolations are tokenized when they are parsed.
+        assert_eq!(
+            attrs[0].component_values.as_deref(),
+            Some(
+                &[ComponentValue::Function {
+                    name: "calc".into(),
+                    args: vec![
+                        ComponentValue::Percentage("100".into()),
+                        ComponentValue::Whitespace,
+                        ComponentValue::Delim('-'),
+                        ComponentValue::Whitespace,
+                        ComponentValue::Dimension {
+                            value: "1".into(),
+                            unit: "px".into(),
+                        },
+                    ],
+                }][..]
+            )
+        );
+        assert!(matches!(attrs[0].component_values(), Cow::Borrowed(_)));
+        assert_eq!(attrs[1].component_values, None);
+    }
+
+    #[test]
+    fn test_spans() {
+        let test_str =
+            "color: red;\n.a > b:not(.c) {\n    width: 1px;\n}\n@media print {\n    .d { }\n}";
+        let parsed = Parser::parse(test_str).expect("Failed to Parse Style");
+
+        let text = |span: Option<Span>| span.expect("no span").source_text(test_str);
+
+        let ScopeContent::Block(ref dangling) = parsed[0] else {
+            panic!("expected a block");
+        };
+        assert_eq!(text(dangling.span), "color: red;");
+        let RuleBlockContent::StyleAttr(ref attr) = dangling.content[0] else {
+            panic!("expected a style attribute");
+        };
+        assert_eq!(text(attr.span), "color: red");
+
+        let ScopeContent::Block(ref block) = parsed[1] else {
+            panic!("expected a block");
+        };
+        assert_eq!(text(block.span), ".a > b:not(.c) {\n    width: 1px;\n}");
+        assert_eq!(text(block.condition[0].span), ".a > b:not(.c)");
+        assert_eq!(block.span.unwrap().line_column(test_str), (2, 1));
+
+        let ScopeContent::Rule(ref rule) = parsed[2] else {
+            panic!("expected a rule");
+        };
+        assert_eq!(text(rule.span), "@media print {\n    .d { }\n}");
+        let RuleBlockContent::Block(ref block) = rule.content[0] else {
+            panic!("expected a block");
+        };
+        assert_eq!(text(block.span), ".d { }");
+        assert_eq!(block.span.unwrap().line_column(test_str), (6, 5));
+    }
+
+    #[test]
+    fn test_error_details() {
+        let test_str = ".a {\n    color: red;\n";
+
+        let details = match Parser::parse(test_str) {
+            Err(Error::Parse { details, .. }) => details.expect("no details"),
+            m => panic!("expected a parse error, got: {m:?}"),
+        };
+
+        assert_eq!(
+            details,
+            ParseErrorDetails {
+                offset: test_str.len(),
+                line: 3,
+                column: 1,
+                expected: Some("'}'".to_string()),
+                snippet: "".to_string(),
+            }
+        );
+
+        let test_str = "color: red;\n.a {\n    width: 1px;\n    @media print {\n}";
+
+        let details = match Parser::parse(test_str) {
+            Err(Error::Parse { details, .. }) => details.expect("no details"),
+            m => panic!("expected a parse error, got: {m:?}"),
+        };
+
+        assert_eq!(details.line, 5);
+        assert_eq!(details.expected.as_deref(), Some("'}'"));
+    }
+
+    #[test]
+    fn test_parse_lossy() {
+        init();
+        let test_str = r#"color: red;
+.a {
+    width 1px;
+    height: 1px;
+}
+a > > b {
+    color: blue;
+}
+}
+.c {
+    display: none;"#;
+
+        let (parsed, diagnostics) = Parser::parse_lossy(test_str);
+
+        let expected = Sheet::from(vec![
+            ScopeContent::Block(Block {
+                condition: Cow::Borrowed(&[]),
+                content: vec![StyleAttribute {
+                    key: "color".into(),
+                    value: vec!["red".into()].into(),
+                    important: false,
+                    component_values: None,
+                    span: None,
+                }
+                .into()]
+                .into(),
+                span: None,
+            }),
+            ScopeContent::Block(Block {
+                condition: vec![sel(vec![class("a")])].into(),
+                content: vec![StyleAttribute {
+                    key: "height".into(),
+                    value: vec!["1px".into()].into(),
+                    important: false,
+                    component_values: None,
+                    span: None,
+                }
+                .into()]
+                .into(),
+                span: None,
+            }),
+            ScopeContent::Block(Block {
+                condition: vec![sel(vec![class("c")])].into(),
+                content: vec![StyleAttribute {
+                    key: "display".into(),
+                    value: vec!["none".into()].into(),
+                    important: false,
+                    component_values: None,
+                    span: None,
+                }
+                .into()]
+                .into(),
+                span: None,
+            }),
+        ]);
+        assert_eq!(parsed, expected);
+
+        let lines: Vec<_> = diagnostics.iter().map(|m| (m.line, m.column)).collect();
+        assert_eq!(lines, vec![(3, 5), (6, 1), (9, 1), (11, 19)]);
+
+        // Spans do not cover the closing brace added by the parser.
+        let ScopeContent::Block(ref block) = parsed[2] else {
+            panic!("expected a block");
+        };
+        assert_eq!(
+            block.span.unwrap().source_text(test_str),
+            ".c {\n    display: none;"
+        );
+    }
 }
@@ -0,0 +1,30 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+
+use crate::output::{OutputVars, Reify, ReifyContext};
+
+// A hash of the macro input that is the same on every platform, so custom properties of
+// server rendered styles match the ones on the client.
+fn stable_hash(s: &str) -> u32 {
+    s.bytes().fold(0x811c_9dc5, |hash, b| {
+        (hash ^ u32::from(b)).wrapping_mul(0x0100_0193)
+    })
+}
+
+pub(crate) fn macro_fn(input: TokenStream) -> TokenStream {
+    let prefix = format!("stylist-{:08x}", stable_hash(&input.to_string()));
+
+    let mut sheet = match crate::sheet::into_output(input) {
+        Ok(m) => m,
+        Err(e) => return quote! { ::stylist::DynamicStyleSource::new(#e, ::std::vec![]) },
+    };
+
+    let mut vars = OutputVars::new(prefix);
+    vars.extract_sheet(&mut sheet);
+
+    let mut ctx = ReifyContext::new();
+    let sheet_tokens = sheet.into_token_stream(&mut ctx);
+    let vars_tokens = vars.into_token_stream(&mut ctx);
+
+    quote! { ::stylist::DynamicStyleSource::new(#sheet_tokens, #vars_tokens) }
+}
@@ -23,9 +23,9 @@ use syn::{parenthesized, token};
 // css spec
 #[derive(Debug, Clone)]
 pub struct FunctionToken {
-    pub(super) name: CssIdent,
+    pub name: CssIdent,
     pub(super) paren: token::Paren,
-    pub(super) args: Vec<ComponentValue>,
+    pub args: Vec<ComponentValue>,
 }
 
 impl ToTokens for FunctionToken {
@@ -3,15 +3,16 @@ pub mod css_ident;
 
 mod parse;
 
-use crate::output::{Reify, ReifyContext};
+use crate::output::{OutputSheet, Reify, ReifyContext};
 use log::debug;
 use parse::{CssRootNode, IntoOutputContext};
 use proc_macro2::TokenStream;
 
-pub fn macro_fn(input: TokenStream) -> TokenStream {
+/// Parses an inline stylesheet, returns the compile errors if it is invalid.
+pub fn into_output(input: TokenStream) -> Result<OutputSheet, TokenStream> {
     let root = match syn::parse2::<CssRootNode>(input) {
         Ok(parsed) => parsed,
-        Err(failed) => return failed.to_compile_error(),
+        Err(failed) => return Err(failed.to_compile_error()),
     };
 
     debug!("Parsed as: {:?}", root);
@@ -19,10 +20,18 @@ pub fn macro_fn(input: TokenStream) -> TokenStream {
     let mut into_output_ctx = IntoOutputContext::new();
     let output_root = root.into_output(&mut into_output_ctx);
 
-    if let Some(m) = into_output_ctx.into_compile_errors() {
-        m
-    } else {
-        let mut ctx = ReifyContext::new();
-        output_root.into_token_stream(&mut ctx)
+    match into_output_ctx.into_compile_errors() {
+        Some(m) => Err(m),
+        None => Ok(output_root),
+    }
+}
+
+pub fn macro_fn(input: TokenStream) -> TokenStream {
+    match into_output(input) {
+        Ok(m) => {
+            let mut ctx = ReifyContext::new();
+            m.into_token_stream(&mut ctx)
+        }
+        Err(e) => e,
     }
 }
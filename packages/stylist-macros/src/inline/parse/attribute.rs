@@ -19,6 +19,7 @@ pub enum CssAttributeName {
 #[derive(Debug)]
 pub struct CssAttributeValue {
     values: Vec<ComponentValue>,
+    important: bool,
     errors: Vec<ParseError>,
 }
 
@@ -61,6 +62,7 @@ impl Parse for CssAttributeValue {
     fn parse(input: &ParseBuffer) -> ParseResult<Self> {
         let mut component_iter = ComponentValueStream::from(input);
         let mut values = vec![];
+        let mut important = false;
         let mut errors = vec![];
 
         loop {
@@ -71,13 +73,39 @@ impl Parse for CssAttributeValue {
             let next_token = component_iter
                 .next()
                 .ok_or_else(|| input.error("AttributeValue: unexpected end of input"))??;
+
+            // `!important` can only appear at the end of the value.
+            if matches!(next_token, ComponentValue::Token(PreservedToken::Punct(ref p)) if p.as_char() == '!')
+            {
+                let flag = component_iter
+                    .next()
+                    .ok_or_else(|| input.error("AttributeValue: unexpected end of input"))??;
+                match flag {
+                    ComponentValue::Token(PreservedToken::Ident(ref m))
+                        if m.to_output_string().eq_ignore_ascii_case("important")
+                            && input.peek(token::Semi) =>
+                    {
+                        important = true;
+                    }
+                    m => errors.push(ParseError::new_spanned(
+                        m,
+                        "expected `important` at the end of the attribute",
+                    )),
+                }
+                continue;
+            }
+
             let token_errors = next_token.validate_attribute_token();
             if token_errors.is_empty() {
                 values.push(next_token);
             }
             errors.extend(token_errors);
         }
-        Ok(Self { values, errors })
+        Ok(Self {
+            values,
+            important,
+            errors,
+        })
     }
 }
 
@@ -107,6 +135,7 @@ impl CssAttribute {
         OutputAttribute {
             key: self.name.into_output(),
             values,
+            important: self.value.important,
         }
     }
 }
@@ -36,10 +36,6 @@ impl IntoOutputContext {
         self.errors.extend(errors);
     }
 
-    pub fn push_error(&mut self, error: ParseError) {
-        self.errors.push(error);
-    }
-
     pub fn into_compile_errors(self) -> Option<TokenStream> {
         use quote::quote;
 
@@ -1,6 +1,13 @@
-use super::super::component_value::{ComponentValue, ComponentValueStream, PreservedToken};
+use stylist_core::ast::{Combinator, PseudoArgument};
+
+use super::super::component_value::{
+    BlockKind, ComponentValue, ComponentValueStream, FunctionToken, PreservedToken, SimpleBlock,
+};
 use super::{fragment_spacing, IntoOutputContext};
-use crate::output::OutputSelector;
+use crate::output::{
+    OutputCompoundSelector, OutputFragment, OutputPseudoArgument, OutputSelector,
+    OutputSimpleSelector,
+};
 use crate::spacing_iterator::SpacedIterator;
 use itertools::Itertools;
 use proc_macro2::TokenStream;
@@ -49,30 +56,168 @@ impl CssBlockQualifier {
     pub fn into_output(self, ctx: &mut IntoOutputContext) -> Vec<OutputSelector> {
         ctx.extend_errors(self.errors);
 
-        fn is_not_comma(q: &ComponentValue) -> bool {
-            !matches!(q, ComponentValue::Token(PreservedToken::Punct(ref p)) if p.as_char() == ',')
+        into_selector_list(self.qualifiers)
+    }
+}
+
+fn is_punct(q: &ComponentValue, c: char) -> bool {
+    matches!(q, ComponentValue::Token(PreservedToken::Punct(ref p)) if p.as_char() == c)
+}
+
+// We can not detect whitespace in the token stream. A descendant combinator is assumed
+// where a whitespace would be required to separate the tokens, like `fragment_spacing` does.
+fn ends_compound(q: &ComponentValue) -> bool {
+    matches!(
+        q,
+        ComponentValue::Token(PreservedToken::Ident(_))
+            | ComponentValue::Token(PreservedToken::Literal(_))
+            | ComponentValue::Block(_)
+            | ComponentValue::Function(_)
+    ) || is_punct(q, '&')
+        || is_punct(q, '*')
+}
+
+fn starts_compound(q: &ComponentValue) -> bool {
+    matches!(
+        q,
+        ComponentValue::Token(PreservedToken::Ident(_))
+            | ComponentValue::Token(PreservedToken::Literal(_))
+            | ComponentValue::Function(_)
+    ) || is_punct(q, '*')
+        || is_punct(q, '#')
+}
+
+fn spaced_fragments(parts: Vec<ComponentValue>) -> Vec<OutputFragment> {
+    parts
+        .into_iter()
+        .flat_map(|p| p.to_output_fragments())
+        .spaced_with(fragment_spacing)
+        .collect()
+}
+
+fn into_selector_list(parts: Vec<ComponentValue>) -> Vec<OutputSelector> {
+    parts
+        .into_iter()
+        .peekable()
+        .batching(|it| {
+            // Return if no items left
+            it.peek()?;
+            // Take until the next comma
+            let selector_parts = it.peeking_take_while(|m| !is_punct(m, ',')).collect();
+            it.next(); // Consume the comma
+            Some(into_selector(selector_parts))
+        })
+        .collect()
+}
+
+fn into_pseudo(
+    part: Option<ComponentValue>,
+) -> (Vec<OutputFragment>, Option<OutputPseudoArgument>) {
+    match part {
+        Some(ComponentValue::Function(FunctionToken { name, args, .. })) => {
+            let argument = if PseudoArgument::SELECTOR_FUNCTIONS
+                .contains(&name.to_output_string().to_ascii_lowercase().as_str())
+            {
+                OutputPseudoArgument::Selectors(into_selector_list(args))
+            } else {
+                OutputPseudoArgument::Raw(spaced_fragments(args))
+            };
+            (vec![name.into()], Some(argument))
         }
+        Some(m) => (m.to_output_fragments(), None),
+        None => (Vec::new(), None),
+    }
+}
 
-        self.qualifiers
-            .into_iter()
-            .peekable()
-            .batching(|it| {
-                // Return if no items left
-                it.peek()?;
-                // Take until the next comma
-                let selector_parts = it
-                    .peeking_take_while(is_not_comma)
-                    // reify the individual parts
-                    .flat_map(|p| p.to_output_fragments())
-                    // space them correctly
-                    .spaced_with(fragment_spacing)
-                    .collect();
-                let selector = OutputSelector {
-                    selectors: selector_parts,
-                };
-                it.next(); // Consume the comma
-                Some(selector)
-            })
-            .collect()
+fn into_selector(parts: Vec<ComponentValue>) -> OutputSelector {
+    let mut compounds = Vec::new();
+    let mut selectors = Vec::new();
+    let mut combinator = None;
+    let mut pending = None;
+    let mut last_ends_compound = false;
+
+    let mut it = parts.into_iter().peekable();
+    while let Some(part) = it.next() {
+        let explicit_combinator = match part {
+            ComponentValue::Token(PreservedToken::Punct(ref p)) => match p.as_char() {
+                '>' => Some(Combinator::Child),
+                '+' => Some(Combinator::NextSibling),
+                '~' => Some(Combinator::SubsequentSibling),
+                _ => None,
+            },
+            _ => None,
+        };
+        if let Some(m) = explicit_combinator {
+            pending = Some(m);
+            last_ends_compound = false;
+            continue;
+        }
+
+        if pending.is_none() && last_ends_compound && starts_compound(&part) {
+            pending = Some(Combinator::Descendant);
+        }
+
+        let mut last_part = part.clone();
+        let mut next_name = |it: &mut std::iter::Peekable<std::vec::IntoIter<ComponentValue>>| {
+            let next = it.next();
+            if let Some(ref m) = next {
+                last_part = m.clone();
+            }
+            next
+        };
+
+        let selector = match part {
+            m if is_punct(&m, '&') => OutputSimpleSelector::Nesting,
+            m if is_punct(&m, '*') => OutputSimpleSelector::Type(m.to_output_fragments()),
+            m if is_punct(&m, '.') => OutputSimpleSelector::Class(
+                next_name(&mut it)
+                    .map(|m| m.to_output_fragments())
+                    .unwrap_or_default(),
+            ),
+            m if is_punct(&m, '#') => OutputSimpleSelector::Id(
+                next_name(&mut it)
+                    .map(|m| m.to_output_fragments())
+                    .unwrap_or_default(),
+            ),
+            m if is_punct(&m, ':') => {
+                if it.peek().map(|m| is_punct(m, ':')).unwrap_or(false) {
+                    it.next();
+                    let (name, argument) = into_pseudo(next_name(&mut it));
+                    OutputSimpleSelector::PseudoElement { name, argument }
+                } else {
+                    let (name, argument) = into_pseudo(next_name(&mut it));
+                    OutputSimpleSelector::PseudoClass { name, argument }
+                }
+            }
+            ComponentValue::Token(PreservedToken::Ident(m)) => {
+                OutputSimpleSelector::Type(vec![m.into()])
+            }
+            ComponentValue::Block(SimpleBlock {
+                kind: BlockKind::Bracketed(_),
+                contents,
+            }) => OutputSimpleSelector::Attribute(spaced_fragments(contents)),
+            m => OutputSimpleSelector::Raw(m.to_output_fragments()),
+        };
+        last_ends_compound = ends_compound(&last_part);
+
+        if let Some(next) = pending.take() {
+            if !selectors.is_empty() {
+                compounds.push(OutputCompoundSelector {
+                    combinator,
+                    selectors: std::mem::take(&mut selectors),
+                });
+            }
+            combinator = Some(next);
+        }
+        selectors.push(selector);
     }
+
+    if !selectors.is_empty() || combinator.is_some() {
+        compounds.push(OutputCompoundSelector {
+            combinator,
+            selectors,
+        });
+    }
+
+    OutputSelector { compounds }
 }
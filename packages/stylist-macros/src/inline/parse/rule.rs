@@ -3,7 +3,7 @@ use syn::token;
 
 use super::super::component_value::{ComponentValue, ComponentValueStream};
 use super::super::css_ident::CssIdent;
-use super::{fragment_spacing, CssScope, IntoOutputContext};
+use super::{fragment_spacing, CssScope, CssScopeContent, IntoOutputContext};
 use crate::output::{OutputFragment, OutputRule};
 use crate::spacing_iterator::SpacedIterator;
 
@@ -35,7 +35,10 @@ impl Parse for CssAtRule {
         // Recognize the type of @-rule
         // TODO: be sensitive to this detected type when validating the prelude and contained
         // attributes
-        if !["media", "supports"].contains(&name.to_output_string().as_str()) {
+        let is_font_face = name.to_output_string() == "font-face";
+        if !is_font_face
+            && !["media", "supports", "layer"].contains(&name.to_output_string().as_str())
+        {
             errors.push(ParseError::new_spanned(
                 &name,
                 format!("@-rule '{name}' not supported"),
@@ -44,6 +47,7 @@ impl Parse for CssAtRule {
 
         let contents = loop {
             if input.peek(token::Semi) {
+                input.parse::<token::Semi>()?;
                 break CssAtRuleContent::Empty;
             }
             if input.peek(token::Brace) {
@@ -56,6 +60,30 @@ impl Parse for CssAtRule {
             prelude.push(next_token);
         };
 
+        if is_font_face {
+            if !prelude.is_empty() {
+                errors.push(ParseError::new_spanned(
+                    &name,
+                    "@font-face does not accept a prelude",
+                ));
+            }
+
+            // @font-face can only contain descriptors.
+            let only_attributes = match contents {
+                CssAtRuleContent::Scope(ref m) => m
+                    .contents
+                    .iter()
+                    .all(|m| matches!(m, CssScopeContent::Attribute(_))),
+                CssAtRuleContent::Empty => true,
+            };
+            if !only_attributes {
+                errors.push(ParseError::new_spanned(
+                    &name,
+                    "@font-face can only contain attributes",
+                ));
+            }
+        }
+
         Ok(Self {
             _at: at,
             name,
@@ -69,9 +97,12 @@ impl Parse for CssAtRule {
 impl CssAtRule {
     pub fn condition_output(&self) -> Vec<OutputFragment> {
         let mut prelude = vec![OutputFragment::Str(format!(
-            "@{} ",
+            "@{}",
             self.name.to_output_string()
         ))];
+        if !self.prelude.is_empty() {
+            prelude.push(OutputFragment::Str(" ".into()));
+        }
         prelude.extend(
             self.prelude
                 .clone()
@@ -90,6 +121,10 @@ impl CssAtRule {
         OutputRule {
             condition,
             content: match self.contents {
+                // Attributes of @font-face are not wrapped in a block.
+                CssAtRuleContent::Scope(m) if self.name.to_output_string() == "font-face" => {
+                    m.into_rule_block_output(ctx)
+                }
                 CssAtRuleContent::Scope(m) => m.into_rule_output(ctx),
                 CssAtRuleContent::Empty => Vec::new(),
             },
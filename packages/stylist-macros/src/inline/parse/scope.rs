@@ -1,6 +1,6 @@
 use std::mem;
 
-use syn::parse::{Error as ParseError, Parse, ParseBuffer, Result as ParseResult};
+use syn::parse::{Parse, ParseBuffer, Result as ParseResult};
 use syn::{braced, token};
 
 use super::{CssAttribute, CssQualifiedRule, CssScopeContent, IntoOutputContext};
@@ -79,10 +79,7 @@ impl CssScope {
                 }
 
                 CssScopeContent::Nested(m) => {
-                    ctx.push_error(ParseError::new_spanned(
-                        m.qualifier,
-                        "Can not nest qualified blocks (yet)",
-                    ));
+                    contents.push(OutputRuleBlockContent::Block(Box::new(m.into_output(ctx))));
                 }
             }
         }
@@ -1,7 +1,9 @@
-use itertools::Itertools;
+use itertools::{Itertools, MultiPeek};
 use syn::parse::{Parse, ParseBuffer, Result as ParseResult};
 
-use super::super::component_value::{ComponentValue, ComponentValueStream, PreservedToken};
+use super::super::component_value::{
+    BlockKind, ComponentValue, ComponentValueStream, PreservedToken, SimpleBlock,
+};
 use super::{CssAtRule, CssAttribute, CssQualifiedRule};
 
 #[derive(Debug)]
@@ -34,7 +36,7 @@ impl Parse for CssScopeContent {
             // peek another token to see if it's colon
             let maybe_colon = component_peek.peek();
             if let Some(Ok(ComponentValue::Token(PreservedToken::Punct(p)))) = maybe_colon {
-                if p.as_char() == ':' {
+                if p.as_char() == ':' && !Self::peek_braced_block(&mut component_peek) {
                     let attr = input.parse()?;
                     return Ok(Self::Attribute(attr));
                 }
@@ -47,6 +49,28 @@ impl Parse for CssScopeContent {
 }
 
 impl CssScopeContent {
+    // A nested qualified rule such as `a:hover { ... }` starts like an attribute.
+    // It is only an attribute if we reach a ';' before a '{'-block.
+    fn peek_braced_block<I>(component_peek: &mut MultiPeek<I>) -> bool
+    where
+        I: Iterator<Item = ParseResult<ComponentValue>>,
+    {
+        while let Some(Ok(next_input)) = component_peek.peek() {
+            match next_input {
+                ComponentValue::Token(PreservedToken::Punct(p)) if p.as_char() == ';' => {
+                    return false;
+                }
+                ComponentValue::Block(SimpleBlock {
+                    kind: BlockKind::Braced(_),
+                    ..
+                }) => return true,
+                _ => {}
+            }
+        }
+
+        false
+    }
+
     // §5.4.1: Consume a list of rules
     pub fn consume_list_of_rules(input: &ParseBuffer) -> ParseResult<Vec<Self>> {
         let mut contents = Vec::new();
@@ -13,6 +13,7 @@ mod inline;
 mod literal;
 
 mod css;
+mod css_vars;
 mod global_style;
 mod output;
 mod sheet;
@@ -46,6 +47,12 @@ pub fn css(input: TokenStream) -> TokenStream {
     css::macro_fn(input.into()).into()
 }
 
+#[proc_macro]
+#[proc_macro_error]
+pub fn css_vars(input: TokenStream) -> TokenStream {
+    css_vars::macro_fn(input.into()).into()
+}
+
 #[proc_macro]
 #[proc_macro_error]
 pub fn use_style(input: TokenStream) -> TokenStream {
@@ -122,6 +122,7 @@ impl Parser {
             // Converting to String, primarily due to lifetime requirements.
             Err(nom::Err::Error(e)) | Err(nom::Err::Failure(e)) => Err(Error::Parse {
                 reason: convert_error(s, e.clone()),
+                details: None,
                 source: Some(VerboseError {
                     errors: e
                         .errors
@@ -133,6 +134,7 @@ impl Parser {
             Err(nom::Err::Incomplete(e)) => Err(Error::Parse {
                 reason: format!("{e:#?}"),
                 source: None,
+                details: None,
             }),
             Ok((_, res)) => Ok(res),
         }
@@ -15,9 +15,21 @@ mod to_output_with_args;
 use argument::Argument;
 use to_output_with_args::ToOutputWithArgs;
 
-use crate::output::{Reify, ReifyContext};
+use crate::output::{OutputSheet, Reify, ReifyContext};
 
 pub(crate) fn macro_fn(input: TokenStream) -> TokenStream {
+    match into_output(input) {
+        Ok(m) => {
+            let mut ctx = ReifyContext::new();
+            m.into_token_stream(&mut ctx)
+        }
+        Err(e) => e,
+    }
+}
+
+/// Parses a string literal and its arguments, returns the compile errors if the literal is
+/// invalid and aborts for other errors.
+pub(crate) fn into_output(input: TokenStream) -> Result<OutputSheet, TokenStream> {
     let mut tokens = input.into_iter();
 
     let first_token = match tokens.next() {
@@ -27,7 +39,7 @@ pub(crate) fn macro_fn(input: TokenStream) -> TokenStream {
 
     let s_literal = match StringLit::try_from(first_token.clone()) {
         Ok(m) => m,
-        Err(e) => return e.to_compile_error2(),
+        Err(e) => return Err(e.to_compile_error2()),
     };
 
     let sheet: Sheet = match s_literal.value().parse() {
@@ -129,6 +141,5 @@ pub(crate) fn macro_fn(input: TokenStream) -> TokenStream {
         }
     }
 
-    let mut ctx = ReifyContext::new();
-    output.into_token_stream(&mut ctx)
+    Ok(output)
 }
@@ -5,8 +5,9 @@ use proc_macro_error::abort_call_site;
 use stylist_core::ast::*;
 
 use crate::output::{
-    OutputAttribute, OutputBlock, OutputFragment, OutputRule, OutputRuleBlockContent,
-    OutputScopeContent, OutputSelector, OutputSheet,
+    OutputAttribute, OutputBlock, OutputCompoundSelector, OutputFragment, OutputPseudoArgument,
+    OutputRule, OutputRuleBlockContent, OutputScopeContent, OutputSelector, OutputSheet,
+    OutputSimpleSelector,
 };
 
 use super::argument::Argument;
@@ -22,6 +23,84 @@ pub(crate) trait ToOutputWithArgs {
     ) -> Self::Output;
 }
 
+impl ToOutputWithArgs for [StringFragment] {
+    type Output = Vec<OutputFragment>;
+
+    fn to_output_with_args(
+        &self,
+        args: &HashMap<String, Argument>,
+        args_used: &mut HashSet<String>,
+    ) -> Self::Output {
+        let mut fragments = Vec::new();
+
+        for frag in self.iter() {
+            fragments.extend(frag.to_output_with_args(args, args_used));
+        }
+
+        fragments
+    }
+}
+
+impl ToOutputWithArgs for PseudoArgument {
+    type Output = OutputPseudoArgument;
+
+    fn to_output_with_args(
+        &self,
+        args: &HashMap<String, Argument>,
+        args_used: &mut HashSet<String>,
+    ) -> Self::Output {
+        match self {
+            Self::Selectors(ref m) => OutputPseudoArgument::Selectors(
+                m.iter()
+                    .map(|m| m.to_output_with_args(args, args_used))
+                    .collect(),
+            ),
+            Self::Raw(ref m) => OutputPseudoArgument::Raw(m.to_output_with_args(args, args_used)),
+        }
+    }
+}
+
+impl ToOutputWithArgs for SimpleSelector {
+    type Output = OutputSimpleSelector;
+
+    fn to_output_with_args(
+        &self,
+        args: &HashMap<String, Argument>,
+        args_used: &mut HashSet<String>,
+    ) -> Self::Output {
+        match self {
+            Self::Nesting => OutputSimpleSelector::Nesting,
+            Self::Type(ref m) => OutputSimpleSelector::Type(m.to_output_with_args(args, args_used)),
+            Self::Class(ref m) => {
+                OutputSimpleSelector::Class(m.to_output_with_args(args, args_used))
+            }
+            Self::Id(ref m) => OutputSimpleSelector::Id(m.to_output_with_args(args, args_used)),
+            Self::Attribute(ref m) => {
+                OutputSimpleSelector::Attribute(m.to_output_with_args(args, args_used))
+            }
+            Self::PseudoClass {
+                ref name,
+                ref argument,
+            } => OutputSimpleSelector::PseudoClass {
+                name: name.to_output_with_args(args, args_used),
+                argument: argument
+                    .as_ref()
+                    .map(|m| m.to_output_with_args(args, args_used)),
+            },
+            Self::PseudoElement {
+                ref name,
+                ref argument,
+            } => OutputSimpleSelector::PseudoElement {
+                name: name.to_output_with_args(args, args_used),
+                argument: argument
+                    .as_ref()
+                    .map(|m| m.to_output_with_args(args, args_used)),
+            },
+            Self::Raw(ref m) => OutputSimpleSelector::Raw(m.to_output_with_args(args, args_used)),
+        }
+    }
+}
+
 impl ToOutputWithArgs for Selector {
     type Output = OutputSelector;
 
@@ -30,12 +109,20 @@ impl ToOutputWithArgs for Selector {
         args: &HashMap<String, Argument>,
         args_used: &mut HashSet<String>,
     ) -> Self::Output {
-        let mut selectors = Vec::new();
-
-        for frag in self.fragments.iter() {
-            selectors.extend(frag.to_output_with_args(args, args_used));
+        let mut compounds = Vec::new();
+
+        for compound in self.compounds.iter() {
+            compounds.push(OutputCompoundSelector {
+                combinator: compound.combinator,
+                selectors: compound
+                    .selectors
+                    .iter()
+                    .map(|m| m.to_output_with_args(args, args_used))
+                    .collect(),
+            });
         }
-        OutputSelector { selectors }
+
+        OutputSelector { compounds }
     }
 }
 
@@ -83,6 +170,7 @@ impl ToOutputWithArgs for StyleAttribute {
         OutputAttribute {
             key: key.into(),
             values,
+            important: self.important,
         }
     }
 }
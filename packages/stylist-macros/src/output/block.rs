@@ -21,6 +21,7 @@ impl Reify for OutputBlock {
             ::stylist::ast::Block {
                 condition: #condition,
                 content: #content,
+                span: ::std::option::Option::None,
             }
         }
     }
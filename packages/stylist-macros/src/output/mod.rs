@@ -14,6 +14,7 @@ mod selector;
 mod sheet;
 mod str_frag;
 mod style_attr;
+mod vars;
 
 mod context;
 mod maybe_static;
@@ -23,10 +24,13 @@ pub use cow_str::OutputCowString;
 pub use rule::OutputRule;
 pub use rule_block_content::OutputRuleBlockContent;
 pub use scope_content::OutputScopeContent;
-pub use selector::OutputSelector;
+pub use selector::{
+    OutputCompoundSelector, OutputPseudoArgument, OutputSelector, OutputSimpleSelector,
+};
 pub use sheet::OutputSheet;
 pub use str_frag::{fragment_coalesce, OutputFragment};
 pub use style_attr::OutputAttribute;
+pub use vars::OutputVars;
 
 pub use context::ReifyContext;
 pub use maybe_static::IntoCowVecTokens;
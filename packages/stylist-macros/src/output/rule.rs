@@ -29,6 +29,7 @@ impl Reify for OutputRule {
                     #condition
                 },
                 content: #content,
+                span: ::std::option::Option::None,
             }
         }
     }
@@ -2,23 +2,162 @@ use super::{fragment_coalesce, IntoCowVecTokens, OutputFragment, Reify, ReifyCon
 use itertools::Itertools;
 use proc_macro2::TokenStream;
 use quote::quote;
+use stylist_core::ast::Combinator;
+
+fn fragments_into_token_stream(frags: Vec<OutputFragment>, ctx: &mut ReifyContext) -> TokenStream {
+    frags
+        .into_iter()
+        // optimize successive (string) literals
+        .coalesce(fragment_coalesce)
+        .into_cow_vec_tokens(quote! {::stylist::ast::StringFragment}, ctx)
+}
+
+#[derive(Debug, Clone)]
+pub enum OutputPseudoArgument {
+    Selectors(Vec<OutputSelector>),
+    Raw(Vec<OutputFragment>),
+}
+
+impl Reify for OutputPseudoArgument {
+    fn into_token_stream(self, ctx: &mut ReifyContext) -> TokenStream {
+        match self {
+            Self::Selectors(m) => {
+                let selectors = m.into_cow_vec_tokens(quote! {::stylist::ast::Selector}, ctx);
+                quote! { ::stylist::ast::PseudoArgument::Selectors(#selectors) }
+            }
+            Self::Raw(m) => {
+                let frags = fragments_into_token_stream(m, ctx);
+                quote! { ::stylist::ast::PseudoArgument::Raw(#frags) }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum OutputSimpleSelector {
+    Nesting,
+    Type(Vec<OutputFragment>),
+    Class(Vec<OutputFragment>),
+    Id(Vec<OutputFragment>),
+    Attribute(Vec<OutputFragment>),
+    PseudoClass {
+        name: Vec<OutputFragment>,
+        argument: Option<OutputPseudoArgument>,
+    },
+    PseudoElement {
+        name: Vec<OutputFragment>,
+        argument: Option<OutputPseudoArgument>,
+    },
+    Raw(Vec<OutputFragment>),
+}
+
+impl Reify for OutputSimpleSelector {
+    fn into_token_stream(self, ctx: &mut ReifyContext) -> TokenStream {
+        let pseudo_parts =
+            |name, argument: Option<OutputPseudoArgument>, ctx: &mut ReifyContext| {
+                let name = fragments_into_token_stream(name, ctx);
+                let argument = match argument {
+                    Some(m) => {
+                        let argument = m.into_token_stream(ctx);
+                        quote! { ::std::option::Option::Some(#argument) }
+                    }
+                    None => quote! { ::std::option::Option::None },
+                };
+                (name, argument)
+            };
+
+        match self {
+            Self::Nesting => quote! { ::stylist::ast::SimpleSelector::Nesting },
+            Self::Type(m) => {
+                let frags = fragments_into_token_stream(m, ctx);
+                quote! { ::stylist::ast::SimpleSelector::Type(#frags) }
+            }
+            Self::Class(m) => {
+                let frags = fragments_into_token_stream(m, ctx);
+                quote! { ::stylist::ast::SimpleSelector::Class(#frags) }
+            }
+            Self::Id(m) => {
+                let frags = fragments_into_token_stream(m, ctx);
+                quote! { ::stylist::ast::SimpleSelector::Id(#frags) }
+            }
+            Self::Attribute(m) => {
+                let frags = fragments_into_token_stream(m, ctx);
+                quote! { ::stylist::ast::SimpleSelector::Attribute(#frags) }
+            }
+            Self::PseudoClass { name, argument } => {
+                let (name, argument) = pseudo_parts(name, argument, ctx);
+                quote! {
+                    ::stylist::ast::SimpleSelector::PseudoClass {
+                        name: #name,
+                        argument: #argument,
+                    }
+                }
+            }
+            Self::PseudoElement { name, argument } => {
+                let (name, argument) = pseudo_parts(name, argument, ctx);
+                quote! {
+                    ::stylist::ast::SimpleSelector::PseudoElement {
+                        name: #name,
+                        argument: #argument,
+                    }
+                }
+            }
+            Self::Raw(m) => {
+                let frags = fragments_into_token_stream(m, ctx);
+                quote! { ::stylist::ast::SimpleSelector::Raw(#frags) }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct OutputCompoundSelector {
+    pub combinator: Option<Combinator>,
+    pub selectors: Vec<OutputSimpleSelector>,
+}
+
+impl Reify for OutputCompoundSelector {
+    fn into_token_stream(self, ctx: &mut ReifyContext) -> TokenStream {
+        let combinator = match self.combinator {
+            Some(m) => {
+                let variant = match m {
+                    Combinator::Descendant => quote! { Descendant },
+                    Combinator::Child => quote! { Child },
+                    Combinator::NextSibling => quote! { NextSibling },
+                    Combinator::SubsequentSibling => quote! { SubsequentSibling },
+                };
+                quote! { ::std::option::Option::Some(::stylist::ast::Combinator::#variant) }
+            }
+            None => quote! { ::std::option::Option::None },
+        };
+        let selectors = self
+            .selectors
+            .into_cow_vec_tokens(quote! {::stylist::ast::SimpleSelector}, ctx);
+
+        quote! {
+            ::stylist::ast::CompoundSelector {
+                combinator: #combinator,
+                selectors: #selectors,
+            }
+        }
+    }
+}
 
 #[derive(Debug, Clone)]
 pub struct OutputSelector {
-    pub selectors: Vec<OutputFragment>,
+    pub compounds: Vec<OutputCompoundSelector>,
 }
 
 impl Reify for OutputSelector {
     fn into_token_stream(self, ctx: &mut ReifyContext) -> TokenStream {
-        let parts = self
-            .selectors
-            .into_iter()
-            // optimize successive (string) literals
-            .coalesce(fragment_coalesce)
-            .into_cow_vec_tokens(quote! {::stylist::ast::StringFragment}, ctx);
+        let compounds = self
+            .compounds
+            .into_cow_vec_tokens(quote! {::stylist::ast::CompoundSelector}, ctx);
+
         quote! {
             ::stylist::ast::Selector {
-                fragments: #parts,
+                compounds: #compounds,
+                span: ::std::option::Option::None,
             }
         }
     }
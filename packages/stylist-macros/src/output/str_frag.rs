@@ -89,7 +89,7 @@ impl OutputFragment {
         }
     }
 
-    fn as_string(&self) -> Option<String> {
+    pub(super) fn as_string(&self) -> Option<String> {
         if let OutputCowString::Str(s) = self.clone().into_inner() {
             Some(s)
         } else {
@@ -1,6 +1,9 @@
+use std::borrow::Cow;
+
 use itertools::Itertools;
 use proc_macro2::TokenStream;
 use quote::quote;
+use stylist_core::ast::ComponentValue;
 
 use super::{
     fragment_coalesce, IntoCowVecTokens, OutputCowString, OutputFragment, Reify, ReifyContext,
@@ -10,23 +13,100 @@ use super::{
 pub struct OutputAttribute {
     pub key: OutputCowString,
     pub values: Vec<OutputFragment>,
+    pub important: bool,
 }
 
 impl Reify for OutputAttribute {
     fn into_token_stream(self, ctx: &mut ReifyContext) -> TokenStream {
         let key = self.key.into_token_stream(ctx);
-        let value_parts = self
+        let important = self.important;
+        let values: Vec<_> = self
             .values
             .into_iter()
             .coalesce(fragment_coalesce)
-            .into_cow_vec_tokens(quote! {::stylist::ast::StringFragment}, ctx);
+            .collect();
+
+        // Values known at compile time are tokenized here, the others are tokenized on demand.
+        let component_values = match values
+            .iter()
+            .map(|m| m.as_string())
+            .collect::<Option<String>>()
+        {
+            Some(m) => {
+                let component_values = ComponentValue::tokenize(&m)
+                    .into_cow_vec_tokens(quote! {::stylist::ast::ComponentValue}, ctx);
+                quote! { ::std::option::Option::Some(#component_values) }
+            }
+            None => quote! { ::std::option::Option::None },
+        };
+        let value_parts = values.into_cow_vec_tokens(quote! {::stylist::ast::StringFragment}, ctx);
         quote! {
             ::stylist::ast::StyleAttribute {
                 key: #key,
                 value: {
                     #value_parts
                 },
+                important: #important,
+                component_values: #component_values,
+                span: ::std::option::Option::None,
+            }
+        }
+    }
+}
+
+impl Reify for ComponentValue {
+    fn into_token_stream(self, ctx: &mut ReifyContext) -> TokenStream {
+        let mut cow =
+            |m: Cow<'static, str>| OutputCowString::from(m.into_owned()).into_token_stream(ctx);
+
+        match self {
+            Self::Ident(m) => {
+                let m = cow(m);
+                quote! { ::stylist::ast::ComponentValue::Ident(#m) }
+            }
+            Self::Number(m) => {
+                let m = cow(m);
+                quote! { ::stylist::ast::ComponentValue::Number(#m) }
+            }
+            Self::Dimension { value, unit } => {
+                let value = cow(value);
+                let unit = cow(unit);
+                quote! {
+                    ::stylist::ast::ComponentValue::Dimension {
+                        value: #value,
+                        unit: #unit,
+                    }
+                }
+            }
+            Self::Percentage(m) => {
+                let m = cow(m);
+                quote! { ::stylist::ast::ComponentValue::Percentage(#m) }
+            }
+            Self::String(m) => {
+                let m = cow(m);
+                quote! { ::stylist::ast::ComponentValue::String(#m) }
+            }
+            Self::Url(m) => {
+                let m = cow(m);
+                quote! { ::stylist::ast::ComponentValue::Url(#m) }
+            }
+            Self::Hash(m) => {
+                let m = cow(m);
+                quote! { ::stylist::ast::ComponentValue::Hash(#m) }
+            }
+            Self::Function { name, args } => {
+                let name = cow(name);
+                let args: Vec<_> = args.into_iter().map(|m| m.into_token_stream(ctx)).collect();
+                ctx.uses_static(); // ::std::vec!
+                quote! {
+                    ::stylist::ast::ComponentValue::Function {
+                        name: #name,
+                        args: ::std::vec![#( #args, )*],
+                    }
+                }
             }
+            Self::Delim(m) => quote! { ::stylist::ast::ComponentValue::Delim(#m) },
+            Self::Whitespace => quote! { ::stylist::ast::ComponentValue::Whitespace },
         }
     }
 }
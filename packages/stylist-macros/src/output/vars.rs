@@ -0,0 +1,108 @@
+use itertools::Itertools;
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+use syn::LitStr;
+
+use super::{
+    fragment_coalesce, OutputAttribute, OutputFragment, OutputRule, OutputRuleBlockContent,
+    OutputScopeContent, OutputSheet, Reify, ReifyContext,
+};
+
+// At-rules whose attributes can not use custom properties.
+const SKIPPED_AT_RULES: &[&str] = &["@font-face", "@keyframes", "@-webkit-keyframes"];
+
+/// Values of style attributes that are replaced with CSS custom properties.
+///
+/// Attributes with a value that refers to local variables are rewritten to
+/// `var(--{prefix}-{index})` and the value is set as the custom property at runtime, so the
+/// stylesheet stays the same when the value changes.
+#[derive(Debug)]
+pub struct OutputVars {
+    prefix: String,
+    vars: Vec<(String, Vec<OutputFragment>)>,
+}
+
+impl OutputVars {
+    pub fn new(prefix: String) -> Self {
+        Self {
+            prefix,
+            vars: Vec::new(),
+        }
+    }
+
+    /// Replaces all interpolated attribute values of the sheet.
+    pub fn extract_sheet(&mut self, sheet: &mut OutputSheet) {
+        for content in sheet.contents.iter_mut() {
+            match content {
+                OutputScopeContent::Rule(m) => self.extract_rule(m),
+                OutputScopeContent::Block(m) => self.extract_contents(&mut m.content),
+            }
+        }
+    }
+
+    fn extract_contents(&mut self, contents: &mut [OutputRuleBlockContent]) {
+        for content in contents.iter_mut() {
+            match content {
+                OutputRuleBlockContent::Rule(m) => self.extract_rule(m),
+                OutputRuleBlockContent::Block(m) => self.extract_contents(&mut m.content),
+                OutputRuleBlockContent::StyleAttr(m) => self.extract_attribute(m),
+            }
+        }
+    }
+
+    fn extract_rule(&mut self, rule: &mut OutputRule) {
+        // The at-keyword is the start of the literal prefix of the condition.
+        let prefix: String = rule.condition.iter().map_while(|m| m.as_string()).collect();
+        let prefix = prefix.trim_start().to_ascii_lowercase();
+
+        if SKIPPED_AT_RULES.iter().any(|m| prefix.starts_with(m)) {
+            return;
+        }
+
+        self.extract_contents(&mut rule.content);
+    }
+
+    fn extract_attribute(&mut self, attr: &mut OutputAttribute) {
+        // Values that can be created statically are kept in the stylesheet.
+        let mut ctx = ReifyContext::new();
+        for value in attr.values.iter() {
+            value.clone().into_inner().into_token_stream(&mut ctx);
+        }
+        if ctx.is_static() {
+            return;
+        }
+
+        let name = format!("--{}-{}", self.prefix, self.vars.len());
+        let value = vec![OutputFragment::Str(format!("var({name})"))];
+        self.vars
+            .push((name, std::mem::replace(&mut attr.values, value)));
+    }
+}
+
+impl Reify for OutputVars {
+    fn into_token_stream(self, ctx: &mut ReifyContext) -> TokenStream {
+        let vars = self.vars.into_iter().map(|(name, values)| {
+            let name = LitStr::new(&name, Span::call_site());
+            let parts = values
+                .into_iter()
+                .coalesce(fragment_coalesce)
+                .map(|m| m.into_inner().into_token_stream(ctx));
+
+            quote! {
+                (
+                    ::std::borrow::Cow::<str>::Borrowed(#name),
+                    {
+                        let mut value = ::std::string::String::new();
+                        #({
+                            let part: ::std::borrow::Cow<'_, str> = #parts;
+                            value.push_str(&part);
+                        })*
+                        value
+                    },
+                )
+            }
+        });
+
+        quote! { ::std::vec![#(#vars,)*] }
+    }
+}
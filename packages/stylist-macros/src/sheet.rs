@@ -1,9 +1,29 @@
 use proc_macro2::{TokenStream, TokenTree};
 
+use crate::output::OutputSheet;
+
+fn is_literal(input: &TokenStream) -> bool {
+    matches!(
+        input.clone().into_iter().next(),
+        Some(TokenTree::Literal(_))
+    )
+}
+
 pub(crate) fn macro_fn(input: TokenStream) -> TokenStream {
-    if let Some(TokenTree::Literal(_)) = input.clone().into_iter().next() {
+    if is_literal(&input) {
         crate::literal::macro_fn(input)
     } else {
         crate::inline::macro_fn(input)
     }
 }
+
+/// Parses either syntax into an [`OutputSheet`].
+///
+/// The error contains the compile errors.
+pub(crate) fn into_output(input: TokenStream) -> Result<OutputSheet, TokenStream> {
+    if is_literal(&input) {
+        crate::literal::into_output(input)
+    } else {
+        crate::inline::into_output(input)
+    }
+}
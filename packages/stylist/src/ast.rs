@@ -5,7 +5,9 @@
 //! └── Vec<enum ScopeContent>
 //!     ├── Block
 //!     │   ├── condition: Vec<Selector>
-//!     │   │   └── fragments: Vec<StringFragment>
+//!     │   │   └── compounds: Vec<CompoundSelector>
+//!     │   │       ├── combinator: Option<Combinator>
+//!     │   │       └── selectors: Vec<enum SimpleSelector>
 //!     │   └── content: Vec<enum RuleBlockContent>
 //!     │       ├── StyleAttr
 //!     │       │   ├── key: String
@@ -1,6 +1,5 @@
-use std::rc::Rc;
-
 use crate::manager::{StyleContent, StyleId, StyleKey, StyleManager};
+use crate::sync::Rc;
 use crate::{Result, StyleSource};
 
 /// A struct that represents a global Style.
@@ -9,7 +8,9 @@ use crate::{Result, StyleSource};
 ///
 /// It will replace Current Selectors (`&`) with `:root` and apply dangling style attributes to
 /// the root element (`html` when style is not applied in a Shadow DOM).
-#[derive(Debug, Clone)]
+///
+/// Unlike [`Style`](crate::Style), the names of `@keyframes` rules are kept unchanged.
+#[derive(Debug)]
 pub struct GlobalStyle {
     inner: Rc<StyleContent>,
 }
@@ -18,6 +19,7 @@ impl GlobalStyle {
     // The big method is monomorphic, so less code duplication and code bloat through generics
     // and inlining
     fn create_impl(css: StyleSource, manager: StyleManager) -> Result<Self> {
+        let location = css.location().map(str::to_string);
         let css = css.into_sheet();
 
         // Creates the StyleKey, return from registry if already cached.
@@ -27,7 +29,7 @@ impl GlobalStyle {
             ast: css,
         };
 
-        let inner = manager.get_or_register_style(key)?;
+        let inner = manager.get_or_register_style(key, location.as_deref())?;
         let new_style = Self { inner };
 
         Ok(new_style)
@@ -59,7 +61,9 @@ impl GlobalStyle {
         M: Into<StyleManager>,
     {
         let mgr = manager.into();
-        Self::create_impl(css.try_into()?, mgr)
+        let css = mgr.with_sheet_cache(|| css.try_into())?;
+
+        Self::create_impl(css, mgr)
     }
 
     /// Get the parsed and generated style in `&str`.
@@ -98,15 +102,41 @@ impl GlobalStyle {
     }
 }
 
+impl Clone for GlobalStyle {
+    fn clone(&self) -> Self {
+        self.inner.retain();
+
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl Drop for GlobalStyle {
+    fn drop(&mut self) {
+        self.inner.release();
+    }
+}
+
 #[cfg(test)]
 #[cfg(feature = "parser")]
 mod tests {
     use super::*;
+    use crate::manager::OutputOptions;
+
+    // Styles are compared with the pretty output, which is not the default in release builds.
+    fn pretty_manager() -> StyleManager {
+        StyleManager::builder()
+            .output_options(OutputOptions::pretty())
+            .build()
+            .expect("Failed to create manager.")
+    }
 
     #[test]
     fn test_simple() {
         let global_style =
-            GlobalStyle::new("background-color: black;").expect("Failed to create Style.");
+            GlobalStyle::new_with_manager("background-color: black;", pretty_manager())
+                .expect("Failed to create Style.");
         assert_eq!(
             global_style.get_style_str(),
             r#":root {
@@ -118,7 +148,7 @@ mod tests {
 
     #[test]
     fn test_complex() {
-        let global_style = GlobalStyle::new(
+        let global_style = GlobalStyle::new_with_manager(
             r#"
                 background-color: black;
                 .with-class {
@@ -135,6 +165,7 @@ mod tests {
                     border: 1px solid black;
                 }
             "#,
+            pretty_manager(),
         )
         .expect("Failed to create Style.");
 
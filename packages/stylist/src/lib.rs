@@ -31,6 +31,23 @@
 //! }
 //! ```
 //!
+//! Blocks can be nested in other blocks. Selectors of a nested block are resolved against the
+//! selectors of its parent block and `&` refers to the parent selector:
+//!
+//! ```css
+//! .card {
+//!   color: black;
+//!
+//!   .title {
+//!     font-weight: bold;
+//!   }
+//!
+//!   &:hover {
+//!     color: red;
+//!   }
+//! }
+//! ```
+//!
 //! You can also use other CSS at-rules (such as: @keyframes, @supports and @media):
 //!
 //! ```css
@@ -107,6 +124,11 @@
 //!   `debug_assertions` are enabled.
 //! - `ssr`: Disabled by default, this flag enables Server-side Rendering Support.
 //! - `hydration`: Disabled by default, this flag enables Server-side Rendering Hydration Support.
+//! - `sync`: Disabled by default, this flag makes [`Style`], [`GlobalStyle`] and
+//!   [`StyleManager`](manager::StyleManager) thread-safe, so styles can be rendered on a
+//!   multi-threaded server. Class name generators and backends must be `Send` and `Sync` and the
+//!   container set with `StyleManagerBuilder::container` is ignored, as it is never used on
+//!   non-wasm targets. It has no effect on wasm targets.
 
 #[cfg(any(feature = "yew_use_media_query", target_arch = "wasm32"))]
 mod arch;
@@ -117,6 +139,8 @@ pub mod macros;
 pub mod manager;
 mod style;
 mod style_src;
+mod styled_classes;
+mod sync;
 mod utils;
 #[cfg(feature = "yew")]
 pub mod yew;
@@ -124,6 +148,11 @@ pub mod yew;
 pub use global_style::GlobalStyle;
 pub use style::Style;
 pub use style_src::StyleSource;
+pub use styled_classes::{DynamicStyleSource, StyledClasses};
+#[cfg_attr(documenting, doc(cfg(feature = "parser")))]
+#[cfg(feature = "parser")]
+#[doc(inline)]
+pub use stylist_core::ParseErrorDetails;
 #[doc(inline)]
 pub use stylist_core::{Error, Result};
 /// A procedural macro that parses a string literal or an inline stylesheet into a
@@ -144,6 +173,33 @@ pub use stylist_core::{Error, Result};
 /// ```
 #[cfg(feature = "macros")]
 pub use stylist_macros::css;
+/// A procedural macro like [`css!`] that passes interpolated values of style attributes as CSS
+/// custom properties.
+///
+/// Every style attribute with an interpolated value is replaced with `var(--name)` and the value
+/// is set as the custom property `--name` in a [`DynamicStyleSource`]. The stylesheet stays the
+/// same when the values change, so only one class is created for all values. This is useful for
+/// values that change often, e.g.: in animations.
+///
+/// Interpolated string literals, interpolations in selectors and at-rules and the values of
+/// attributes in `@font-face` and `@keyframes` rules are kept as they are in [`css!`].
+///
+/// # Example
+///
+/// ```
+/// use stylist::{css_vars, StyledClasses};
+/// use yew::prelude::*;
+///
+/// #[function_component(Bar)]
+/// fn bar() -> Html {
+///     let width = 300;
+///     let styled = StyledClasses::new(css_vars!(width: ${width}px;)).unwrap();
+///
+///     html! {<div class={&styled} style={styled.inline_style()} />}
+/// }
+/// ```
+#[cfg(feature = "macros")]
+pub use stylist_macros::css_vars;
 /// A procedural macro that parses a string literal or an inline stylesheet into a
 /// [`GlobalStyle`].
 ///
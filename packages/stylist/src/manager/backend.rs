@@ -0,0 +1,350 @@
+use std::fmt;
+
+use super::{StyleId, StyleKey, StyleManager};
+use crate::sync::{Lock, MaybeSync, Rc};
+use crate::Result;
+
+/// A style that is mounted by a [`StyleBackend`].
+#[derive(Debug)]
+pub struct MountContext<'a> {
+    pub(super) manager: &'a StyleManager,
+    pub(super) key: &'a StyleKey,
+    pub(super) id: &'a StyleId,
+    pub(super) style_str: &'a str,
+}
+
+impl MountContext<'_> {
+    /// The id of the style.
+    pub fn id(&self) -> &StyleId {
+        self.id
+    }
+
+    /// The rendered stylesheet of the style.
+    pub fn style_str(&self) -> &str {
+        self.style_str
+    }
+
+    /// Returns `true` if the style is a [`GlobalStyle`](crate::GlobalStyle).
+    pub fn is_global(&self) -> bool {
+        self.key.is_global
+    }
+
+    /// Returns `true` if the style should be mounted before all other styles.
+    ///
+    /// This is set with [`StyleManagerBuilder::append`](super::StyleManagerBuilder::append).
+    pub fn prepend(&self) -> bool {
+        !self.manager.inner.append
+    }
+
+    /// The manager that mounts the style.
+    pub fn manager(&self) -> &StyleManager {
+        self.manager
+    }
+}
+
+/// The way how a [`StyleManager`] adds styles to and removes them from the document.
+///
+/// It can be set with [`StyleManagerBuilder::backend`](super::StyleManagerBuilder::backend). With
+/// the `sync` feature, backends must be `Send` and `Sync`.
+pub trait StyleBackend: fmt::Debug + MaybeSync {
+    /// Mounts a style.
+    fn mount(&self, ctx: &MountContext<'_>) -> Result<()>;
+
+    /// Unmounts a style that has been mounted by this backend.
+    ///
+    /// The backend may outlive its manager, as long as any of its styles is still in use.
+    fn unmount(&self, id: &StyleId) -> Result<()>;
+
+    /// Takes over a style that has already been mounted, e.g.: when it was rendered on the
+    /// server.
+    ///
+    /// Returns `true` if the backend unmounts the style once it is no longer used.
+    #[allow(unused_variables)]
+    fn hydrate(&self, ctx: &MountContext<'_>) -> bool {
+        false
+    }
+}
+
+/// Mounts every style as its own `<style>` element into the container of the manager.
+///
+/// This is the default backend, it makes the styles easy to find in the developer tools of the
+/// browser. It does nothing on non-wasm targets.
+#[derive(Debug, Default)]
+pub struct DomBackend {
+    #[cfg(target_arch = "wasm32")]
+    elements: std::cell::RefCell<std::collections::HashMap<StyleId, web_sys::Element>>,
+}
+
+#[cfg(target_arch = "wasm32")]
+impl StyleBackend for DomBackend {
+    fn mount(&self, ctx: &MountContext<'_>) -> Result<()> {
+        use crate::Error;
+
+        let manager = ctx.manager();
+        let document = manager.document()?;
+        let container = manager.container().ok_or(Error::Web(None))?;
+
+        let style_element = (|| {
+            let style_element = document.create_element("style")?;
+            style_element.set_attribute("data-style", ctx.id())?;
+            if let Some(ref m) = manager.inner.nonce {
+                style_element.set_attribute("nonce", m)?;
+            }
+            style_element.set_text_content(Some(ctx.style_str()));
+
+            match container.first_child().filter(|_| ctx.prepend()) {
+                Some(m) => container.insert_before(&style_element, Some(&m))?,
+                None => container.append_child(&style_element)?,
+            };
+
+            Ok(style_element)
+        })()
+        .map_err(|e| Error::Web(Some(e)))?;
+
+        self.elements
+            .borrow_mut()
+            .insert(ctx.id().clone(), style_element);
+
+        Ok(())
+    }
+
+    fn unmount(&self, id: &StyleId) -> Result<()> {
+        if let Some(m) = self.elements.borrow_mut().remove(id) {
+            m.remove();
+        }
+
+        Ok(())
+    }
+
+    fn hydrate(&self, ctx: &MountContext<'_>) -> bool {
+        use wasm_bindgen::JsCast;
+        use web_sys::{DocumentFragment, Element};
+
+        let manager = ctx.manager();
+        let selector = format!("style[data-style={}]", ctx.id());
+
+        let element = match manager.container() {
+            Some(m) if m.is_instance_of::<Element>() => {
+                m.unchecked_ref::<Element>().query_selector(&selector)
+            }
+            Some(m) if m.is_instance_of::<DocumentFragment>() => m
+                .unchecked_ref::<DocumentFragment>()
+                .query_selector(&selector),
+            _ => Ok(None),
+        };
+
+        let element = match element.ok().flatten() {
+            Some(m) => Some(m),
+            // The style may have been rendered elsewhere in the document.
+            None => manager
+                .document()
+                .ok()
+                .and_then(|m| m.query_selector(&selector).ok().flatten()),
+        };
+
+        match element {
+            Some(m) => {
+                self.elements.borrow_mut().insert(ctx.id().clone(), m);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl StyleBackend for DomBackend {
+    fn mount(&self, _ctx: &MountContext<'_>) -> Result<()> {
+        // Does nothing on non-wasm targets.
+        Ok(())
+    }
+
+    fn unmount(&self, _id: &StyleId) -> Result<()> {
+        // Does nothing on non-wasm targets.
+        Ok(())
+    }
+}
+
+/// A call made to a [`RecordingBackend`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BackendEvent {
+    /// A style has been mounted.
+    Mount(StyleId),
+    /// A style has been unmounted.
+    Unmount(StyleId),
+}
+
+#[derive(Debug, Default)]
+struct Recording {
+    styles: Vec<(StyleId, String)>,
+    events: Vec<BackendEvent>,
+}
+
+/// A backend that keeps mounted styles in memory.
+///
+/// This can be used to test which styles are mounted and in what order without a browser.
+/// Clones of the backend share the same styles.
+///
+/// # Examples
+///
+/// ```
+/// use stylist::manager::{BackendEvent, RecordingBackend, StyleManager};
+/// use stylist::Style;
+///
+/// let backend = RecordingBackend::new();
+/// let mgr = StyleManager::builder()
+///     .backend(backend.clone())
+///     .build()?;
+///
+/// let style = Style::new_with_manager("color: red;", mgr)?;
+///
+/// assert_eq!(backend.events(), vec![BackendEvent::Mount(style.id().clone())]);
+/// assert_eq!(backend.styles()[0].1, style.get_style_str());
+/// # Ok::<(), stylist::Error>(())
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct RecordingBackend {
+    inner: Rc<Lock<Recording>>,
+}
+
+impl RecordingBackend {
+    /// Creates an empty backend.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The ids and style strings of all mounted styles, in the order they appear in the
+    /// document.
+    pub fn styles(&self) -> Vec<(StyleId, String)> {
+        self.inner.lock().styles.clone()
+    }
+
+    /// All styles that have been mounted and unmounted, in the order it happened.
+    pub fn events(&self) -> Vec<BackendEvent> {
+        self.inner.lock().events.clone()
+    }
+}
+
+impl StyleBackend for RecordingBackend {
+    fn mount(&self, ctx: &MountContext<'_>) -> Result<()> {
+        let mut inner = self.inner.lock();
+
+        let style = (ctx.id().clone(), ctx.style_str().to_string());
+        match ctx.prepend() {
+            true => inner.styles.insert(0, style),
+            false => inner.styles.push(style),
+        }
+        inner.events.push(BackendEvent::Mount(ctx.id().clone()));
+
+        Ok(())
+    }
+
+    fn unmount(&self, id: &StyleId) -> Result<()> {
+        let mut inner = self.inner.lock();
+
+        inner.styles.retain(|m| &m.0 != id);
+        inner.events.push(BackendEvent::Unmount(id.clone()));
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "parser")]
+mod tests {
+    use super::*;
+    use crate::Style;
+
+    fn create(backend: &RecordingBackend, append: bool) -> StyleManager {
+        StyleManager::builder()
+            .backend(backend.clone())
+            .append(append)
+            .build()
+            .expect("Failed to create manager.")
+    }
+
+    #[test]
+    fn test_mount_order() {
+        let backend = RecordingBackend::new();
+        let mgr = create(&backend, true);
+
+        let style_a =
+            Style::new_with_manager("color: red;", &mgr).expect("Failed to create Style.");
+        let style_b =
+            Style::new_with_manager("color: blue;", &mgr).expect("Failed to create Style.");
+        // Styles that are already mounted are not mounted again.
+        Style::new_with_manager("color: red;", &mgr).expect("Failed to create Style.");
+
+        let ids: Vec<_> = backend.styles().into_iter().map(|m| m.0).collect();
+        assert_eq!(ids, vec![style_a.id().clone(), style_b.id().clone()]);
+        assert_eq!(backend.events().len(), 2);
+    }
+
+    #[test]
+    fn test_prepend() {
+        let backend = RecordingBackend::new();
+        let mgr = create(&backend, false);
+
+        let style_a =
+            Style::new_with_manager("color: red;", &mgr).expect("Failed to create Style.");
+        let style_b =
+            Style::new_with_manager("color: blue;", &mgr).expect("Failed to create Style.");
+
+        let ids: Vec<_> = backend.styles().into_iter().map(|m| m.0).collect();
+        assert_eq!(ids, vec![style_b.id().clone(), style_a.id().clone()]);
+    }
+
+    #[test]
+    fn test_unmount() {
+        let backend = RecordingBackend::new();
+        let mgr = create(&backend, true);
+
+        let style = Style::new_with_manager("color: red;", &mgr).expect("Failed to create Style.");
+        let id = style.id().clone();
+
+        // Styles are unmounted once they are unregistered and all handles are dropped.
+        style.unregister();
+        assert_eq!(backend.styles().len(), 1);
+        drop(style);
+
+        assert!(backend.styles().is_empty());
+        assert_eq!(
+            backend.events(),
+            vec![BackendEvent::Mount(id.clone()), BackendEvent::Unmount(id)]
+        );
+    }
+
+    #[test]
+    fn test_reentrant_mount() {
+        use crate::GlobalStyle;
+
+        // Creates a global style with the same manager whenever a style is mounted.
+        #[derive(Debug)]
+        struct Reentrant(RecordingBackend);
+
+        impl StyleBackend for Reentrant {
+            fn mount(&self, ctx: &MountContext<'_>) -> Result<()> {
+                if !ctx.is_global() {
+                    GlobalStyle::new_with_manager("margin: 0;", ctx.manager())?;
+                }
+                self.0.mount(ctx)
+            }
+
+            fn unmount(&self, id: &StyleId) -> Result<()> {
+                self.0.unmount(id)
+            }
+        }
+
+        let backend = RecordingBackend::new();
+        let mgr = StyleManager::builder()
+            .backend(Reentrant(backend.clone()))
+            .build()
+            .expect("Failed to create manager.");
+
+        let style = Style::new_with_manager("color: red;", &mgr).expect("Failed to create Style.");
+
+        let ids: Vec<_> = backend.styles().into_iter().map(|m| m.0).collect();
+        assert_eq!(ids.len(), 2);
+        assert_eq!(&ids[1], style.id());
+    }
+}
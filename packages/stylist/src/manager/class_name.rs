@@ -0,0 +1,230 @@
+use std::borrow::Cow;
+use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use super::StyleKey;
+use crate::ast::Sheet;
+use crate::sync::MaybeSync;
+use crate::utils::{get_entropy, hash_to_alphanumeric};
+
+/// Information about a style that a [`ClassNameGenerator`] generates a class name for.
+#[derive(Debug)]
+pub struct ClassNameContext<'a> {
+    pub(super) key: &'a StyleKey,
+    pub(super) location: Option<&'a str>,
+    pub(super) attempt: u32,
+}
+
+impl ClassNameContext<'_> {
+    /// The prefix of the style.
+    ///
+    /// The prefix is not guaranteed to be a valid CSS identifier, class names are escaped after
+    /// they are generated.
+    pub fn prefix(&self) -> &str {
+        &self.key.prefix
+    }
+
+    /// Returns `true` if the class name is generated for a [`GlobalStyle`](crate::GlobalStyle).
+    pub fn is_global(&self) -> bool {
+        self.key.is_global
+    }
+
+    /// The stylesheet of the style.
+    pub fn sheet(&self) -> &Sheet {
+        &self.key.ast
+    }
+
+    /// The source location where the style was created.
+    ///
+    /// This is only available in debug builds with the `debug_style_locations` feature.
+    pub fn location(&self) -> Option<&str> {
+        self.location
+    }
+
+    /// The number of class names that have been generated for this style before.
+    ///
+    /// A class name is generated again if it is already used by another style, generators
+    /// that always return the same class name for the same style should use this to return a
+    /// different one.
+    pub fn attempt(&self) -> u32 {
+        self.attempt
+    }
+
+    /// A hash of the prefix and the content of the style.
+    ///
+    /// The hash is stable across runs, platforms and compiler versions and changes with
+    /// [`attempt`](Self::attempt).
+    pub fn content_hash(&self) -> u64 {
+        self.key.content_hash(self.attempt)
+    }
+
+    // The prefix, followed by `-global` for global styles.
+    fn base(&self) -> String {
+        match self.is_global() {
+            true => format!("{}-global", self.prefix()),
+            false => self.prefix().to_string(),
+        }
+    }
+}
+
+/// A strategy to generate class names of styles.
+///
+/// It can be set with
+/// [`StyleManagerBuilder::class_name_generator`](super::StyleManagerBuilder::class_name_generator).
+///
+/// With the `sync` feature, generators must be `Send` and `Sync`.
+pub trait ClassNameGenerator: fmt::Debug + MaybeSync {
+    /// Generates the class name of a style.
+    fn generate(&self, ctx: &ClassNameContext<'_>) -> String;
+}
+
+/// Class names with 8 random alphanumeric characters, e.g.: `stylist-uSu9NZZu`.
+///
+/// This is the default generator. Without the `random` feature, a counter is used instead.
+#[derive(Debug, Default)]
+pub struct RandomClassNames;
+
+impl ClassNameGenerator for RandomClassNames {
+    fn generate(&self, ctx: &ClassNameContext<'_>) -> String {
+        format!("{}-{}", ctx.base(), get_entropy())
+    }
+}
+
+/// Class names with an increasing number, e.g.: `stylist-0`, `stylist-1`.
+#[derive(Debug, Default)]
+pub struct CounterClassNames {
+    next: AtomicU64,
+}
+
+impl ClassNameGenerator for CounterClassNames {
+    fn generate(&self, ctx: &ClassNameContext<'_>) -> String {
+        format!(
+            "{}-{}",
+            ctx.base(),
+            self.next.fetch_add(1, Ordering::Relaxed)
+        )
+    }
+}
+
+/// Class names derived from a hash of the style, e.g.: `stylist-7cKz0Qb2`.
+///
+/// The same style gets the same class name in every run and on every machine, so server
+/// rendered html can be cached and matched by the client.
+#[derive(Debug, Default)]
+pub struct ContentHashClassNames;
+
+impl ClassNameGenerator for ContentHashClassNames {
+    fn generate(&self, ctx: &ClassNameContext<'_>) -> String {
+        format!(
+            "{}-{}",
+            ctx.base(),
+            hash_to_alphanumeric(ctx.content_hash())
+        )
+    }
+}
+
+/// Readable class names that contain the source location of the style,
+/// e.g.: `stylist-src-main-rs-12-5-7cKz0Qb2`.
+///
+/// Source locations are only recorded in debug builds with the `debug_style_locations` feature,
+/// otherwise this generates the same names as [`ContentHashClassNames`].
+#[derive(Debug, Default)]
+pub struct DebugClassNames;
+
+impl ClassNameGenerator for DebugClassNames {
+    fn generate(&self, ctx: &ClassNameContext<'_>) -> String {
+        let hash = hash_to_alphanumeric(ctx.content_hash());
+
+        match ctx.location() {
+            Some(m) => format!("{}-{}-{}", ctx.base(), m, hash),
+            None => format!("{}-{}", ctx.base(), hash),
+        }
+    }
+}
+
+/// The shortest possible class names: `a`, `b`, ... `z`, `aa`, `ab`, ...
+///
+/// The prefix is ignored. Class names are only unique within a manager, so they may clash with
+/// other class names in the document.
+#[derive(Debug, Default)]
+pub struct MinifiedClassNames {
+    next: AtomicU64,
+}
+
+impl ClassNameGenerator for MinifiedClassNames {
+    fn generate(&self, _ctx: &ClassNameContext<'_>) -> String {
+        let mut n = self.next.fetch_add(1, Ordering::Relaxed);
+
+        let mut name = Vec::new();
+        loop {
+            name.push(b'a' + (n % 26) as u8);
+            if n < 26 {
+                break;
+            }
+            n = n / 26 - 1;
+        }
+        name.reverse();
+
+        String::from_utf8(name).expect("class names are always ascii")
+    }
+}
+
+/// Turns a class name into a valid CSS identifier.
+///
+/// Characters that are not allowed are replaced with `-` and a `_` is prepended if the name
+/// starts with a digit.
+pub(crate) fn to_ident(name: &str) -> Cow<'_, str> {
+    let is_valid_char =
+        |c: char| c.is_ascii_alphanumeric() || c == '-' || c == '_' || !c.is_ascii();
+    let starts_with_digit = name
+        .trim_start_matches('-')
+        .starts_with(|c: char| c.is_ascii_digit());
+
+    if name.chars().all(is_valid_char) && !starts_with_digit && !name.is_empty() {
+        return name.into();
+    }
+
+    let mut ident = String::with_capacity(name.len() + 1);
+    if starts_with_digit || name.is_empty() {
+        ident.push('_');
+    }
+    ident.extend(name.chars().map(|c| if is_valid_char(c) { c } else { '-' }));
+
+    ident.into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_minified_class_names() {
+        let gen = MinifiedClassNames::default();
+        let key = StyleKey {
+            is_global: false,
+            prefix: "stylist".into(),
+            ast: Sheet::new(),
+        };
+        let ctx = ClassNameContext {
+            key: &key,
+            location: None,
+            attempt: 0,
+        };
+
+        let names: Vec<String> = (0..28).map(|_| gen.generate(&ctx)).collect();
+        assert_eq!(names[0], "a");
+        assert_eq!(names[25], "z");
+        assert_eq!(names[26], "aa");
+        assert_eq!(names[27], "ab");
+    }
+
+    #[test]
+    fn test_to_ident() {
+        assert_eq!(to_ident("stylist-abc"), "stylist-abc");
+        assert_eq!(to_ident("my component"), "my-component");
+        assert_eq!(to_ident("src/main.rs:12:5"), "src-main-rs-12-5");
+        assert_eq!(to_ident("1st"), "_1st");
+        assert_eq!(to_ident("-1st"), "_-1st");
+        assert_eq!(to_ident(""), "_");
+    }
+}
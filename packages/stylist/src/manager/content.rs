@@ -1,6 +1,7 @@
-use std::rc::Rc;
+use std::sync::atomic::{AtomicUsize, Ordering};
 
-use crate::manager::{StyleId, StyleKey, StyleManager, WeakStyleManager};
+use crate::manager::{StyleBackend, StyleId, StyleKey, StyleManager, WeakStyleManager};
+use crate::sync::Rc;
 use crate::Result;
 
 #[derive(Debug)]
@@ -8,8 +9,13 @@ pub(crate) struct StyleContent {
     pub id: StyleId,
     pub key: Rc<StyleKey>,
     pub style_str: String,
+    // The backend that unmounts the style, `None` if it is not mounted by the manager, e.g.:
+    // when it has been rendered on the server but not found on the client.
+    pub mounted: Option<Rc<dyn StyleBackend>>,
 
     pub manager: WeakStyleManager,
+    // The number of `Style`s and `GlobalStyle`s that hold this style.
+    pub handles: AtomicUsize,
 }
 
 impl StyleContent {
@@ -22,7 +28,10 @@ impl StyleContent {
     }
 
     pub fn unmount(&self) -> Result<()> {
-        StyleManager::unmount(self.id())
+        match self.mounted {
+            Some(ref m) => m.unmount(self.id()),
+            None => Ok(()),
+        }
     }
 
     pub fn key(&self) -> &Rc<StyleKey> {
@@ -33,6 +42,30 @@ impl StyleContent {
         self.manager.upgrade()
     }
 
+    /// Called when a [`Style`](crate::Style) or [`GlobalStyle`](crate::GlobalStyle) that holds
+    /// this style is created.
+    pub fn retain(&self) {
+        self.handles.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Called when a [`Style`](crate::Style) or [`GlobalStyle`](crate::GlobalStyle) that holds
+    /// this style is dropped.
+    pub fn release(self: &Rc<Self>) {
+        if self.handles.fetch_sub(1, Ordering::AcqRel) != 1 {
+            return;
+        }
+
+        if let Some(mgr) = self.manager() {
+            mgr.release_style(self);
+        }
+    }
+
+    /// Returns `true` if no [`Style`](crate::Style) or [`GlobalStyle`](crate::GlobalStyle) holds
+    /// this style.
+    pub fn is_unused(&self) -> bool {
+        self.handles.load(Ordering::Acquire) == 0
+    }
+
     pub fn unregister(&self) {
         if let Some(mgr) = self.manager() {
             mgr.unregister_style(self.key());
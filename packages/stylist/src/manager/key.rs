@@ -4,8 +4,9 @@ use std::ops::Deref;
 
 use serde::{Deserialize, Serialize};
 
-use crate::ast::Sheet;
-use crate::utils::get_entropy;
+use super::class_name::to_ident;
+use crate::ast::{OutputOptions, Sheet, ToStyleStr};
+use crate::utils::stable_hash;
 
 /// A [`StyleKey`].
 ///
@@ -18,6 +19,34 @@ pub(crate) struct StyleKey {
     pub ast: Sheet,
 }
 
+impl StyleKey {
+    /// A hash of the key that is stable across runs, platforms and compiler versions.
+    ///
+    /// `salt` is used to pick another hash when two keys collide.
+    pub fn content_hash(&self, salt: u32) -> u64 {
+        // The style is rendered with a placeholder class name so the hash does not depend on the
+        // class name it is used to create.
+        let style_str = self
+            .ast
+            .to_style_str_with_options(Some("stylist"), OutputOptions::minified());
+
+        let mut s = format!("{}\0{}\0", self.is_global, self.prefix);
+        if salt > 0 {
+            s.push_str(&salt.to_string());
+        }
+        s.push('\0');
+        s.push_str(&style_str);
+
+        stable_hash(s.as_bytes())
+    }
+
+    /// A digest of the key, styles rendered on the server are found by it when they are hydrated.
+    #[cfg(any(feature = "ssr", feature = "hydration"))]
+    pub fn digest(&self) -> u64 {
+        self.content_hash(0)
+    }
+}
+
 /// The Unique Identifier of a Style.
 ///
 /// This is primarily used by [`StyleManager`](crate::manager::StyleManager) to track the mounted
@@ -39,11 +68,8 @@ impl fmt::Display for StyleId {
 }
 
 impl StyleId {
-    pub(crate) fn new_scoped(prefix: &str) -> StyleId {
-        StyleId(format!("{}-{}", prefix, get_entropy()))
-    }
-
-    pub(crate) fn new_global(prefix: &str) -> StyleId {
-        StyleId(format!("{}-global-{}", prefix, get_entropy()))
+    /// Creates an id from a generated class name, escaping it if necessary.
+    pub(crate) fn from_class_name(name: &str) -> StyleId {
+        StyleId(to_ident(name).into_owned())
     }
 }
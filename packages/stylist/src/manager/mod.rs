@@ -7,37 +7,72 @@
 //! This is an advanced feature and most of the time you don't need to use it.
 
 use std::borrow::Cow;
-use std::cell::RefCell;
-use std::rc::{Rc, Weak};
+use std::sync::atomic::AtomicUsize;
 
-use once_cell::unsync::Lazy;
-use stylist_core::ast::ToStyleStr;
+use stylist_core::ast::{Rule, RuleBlockContent, ScopeContent, Sheet, ToStyleStr};
 use stylist_core::ResultDisplay;
 use web_sys::Node;
 
+mod backend;
+mod class_name;
 mod content;
 mod key;
 mod registry;
+mod scope;
+mod shared_sheet;
 #[cfg(feature = "ssr")]
 mod ssr;
+#[cfg(feature = "ssr")]
+mod stream;
+pub use crate::sync::MaybeSync;
+use crate::sync::{Lock, Rc, Weak};
 use crate::Result;
+pub use backend::{BackendEvent, DomBackend, MountContext, RecordingBackend, StyleBackend};
+pub use class_name::{
+    ClassNameContext, ClassNameGenerator, ContentHashClassNames, CounterClassNames,
+    DebugClassNames, MinifiedClassNames, RandomClassNames,
+};
 pub(crate) use content::StyleContent;
 pub use key::StyleId;
 pub(crate) use key::StyleKey;
-use registry::StyleRegistry;
+use registry::{RegistryGuard, StyleRegistry};
+pub use scope::ScopedFuture;
+pub use shared_sheet::SheetBackend;
+#[doc(inline)]
+pub use stylist_core::ast::OutputOptions;
 
 #[cfg(feature = "ssr")]
 pub use ssr::*;
+#[cfg(feature = "ssr")]
+pub use stream::StreamingReader;
+
+/// The number of unused styles kept by the global default manager on the server.
+#[cfg(all(feature = "ssr", not(target_arch = "wasm32")))]
+const GLOBAL_MAX_UNUSED: usize = 1024;
 
 /// A builder for [`StyleManager`].
 #[derive(Debug)]
 pub struct StyleManagerBuilder {
-    registry: RefCell<StyleRegistry>,
+    registry: Lock<StyleRegistry>,
+    // Styles released while the registry is locked.
+    released: Lock<Vec<(Rc<StyleKey>, Weak<StyleContent>)>>,
 
     prefix: Cow<'static, str>,
+    layer: Option<Cow<'static, str>>,
+    // Web-sys types are not thread-safe, the container is never used on non-wasm targets.
+    #[cfg(any(not(feature = "sync"), target_arch = "wasm32"))]
     container: Option<Node>,
 
     append: bool,
+    // Set when the manager is built, unless a backend has been set explicitly.
+    backend: Option<Rc<dyn StyleBackend>>,
+    class_name_generator: Rc<dyn ClassNameGenerator>,
+    output_options: OutputOptions,
+    max_unused: Option<usize>,
+    nonce: Option<Cow<'static, str>>,
+
+    #[cfg(feature = "parser")]
+    sheet_cache: Option<stylist_core::ast::SheetCache>,
 
     #[cfg(feature = "ssr")]
     style_data: Option<std::sync::Arc<std::sync::Mutex<StyleData>>>,
@@ -46,10 +81,24 @@ pub struct StyleManagerBuilder {
 impl Default for StyleManagerBuilder {
     fn default() -> Self {
         Self {
-            registry: RefCell::default(),
+            registry: Lock::default(),
+            released: Lock::default(),
             prefix: "stylist".into(),
+            layer: None,
+            #[cfg(any(not(feature = "sync"), target_arch = "wasm32"))]
             container: None,
             append: true,
+            backend: None,
+            class_name_generator: Rc::new(RandomClassNames),
+            output_options: if cfg!(debug_assertions) {
+                OutputOptions::pretty()
+            } else {
+                OutputOptions::minified()
+            },
+            max_unused: None,
+            nonce: None,
+            #[cfg(feature = "parser")]
+            sheet_cache: None,
             #[cfg(feature = "ssr")]
             style_data: None,
         }
@@ -74,9 +123,26 @@ impl StyleManagerBuilder {
         self
     }
 
+    /// Set the cascade layer that wraps all styles managed by this manager.
+    ///
+    /// Styles in a layer have a lower precedence than styles that are not in any layer,
+    /// regardless of their specificity.
+    pub fn layer(mut self, value: Cow<'static, str>) -> Self {
+        self.layer = Some(value);
+
+        self
+    }
+
     /// Set the container [`Node`] for all style elements managed by this manager.
+    ///
+    /// With the `sync` feature on non-wasm targets, where web-sys types can not be shared between
+    /// threads, the container is not kept.
+    #[allow(unused_mut, unused_variables)]
     pub fn container(mut self, value: Node) -> Self {
-        self.container = Some(value);
+        #[cfg(any(not(feature = "sync"), target_arch = "wasm32"))]
+        {
+            self.container = Some(value);
+        }
 
         self
     }
@@ -91,6 +157,73 @@ impl StyleManagerBuilder {
         self
     }
 
+    /// Set how styles are mounted into the container.
+    ///
+    /// Default: [`DomBackend`]
+    pub fn backend<B>(mut self, value: B) -> Self
+    where
+        B: StyleBackend + 'static,
+    {
+        self.backend = Some(Rc::new(value));
+        self
+    }
+
+    /// Removes styles that are no longer used.
+    ///
+    /// A style is unused once all [`Style`](crate::Style) and
+    /// [`GlobalStyle`](crate::GlobalStyle) instances of it have been dropped. Up to `max_unused`
+    /// unused styles are kept mounted, so styles that are dropped and created again quickly are
+    /// not mounted again each time. The least recently used ones are removed first.
+    ///
+    /// Only styles that are held are considered used. A style converted into `Classes`, e.g.:
+    /// `class={css!(..)}`, is dropped after the conversion, while `use_style` holds its style for
+    /// the lifetime of the component.
+    ///
+    /// Default: disabled, styles are only removed when they are unregistered.
+    pub fn garbage_collect(mut self, max_unused: usize) -> Self {
+        self.max_unused = Some(max_unused);
+        self
+    }
+
+    /// Set how class names of styles are generated.
+    ///
+    /// Default: [`RandomClassNames`]
+    pub fn class_name_generator<G>(mut self, value: G) -> Self
+    where
+        G: ClassNameGenerator + 'static,
+    {
+        self.class_name_generator = Rc::new(value);
+        self
+    }
+
+    /// Set how styles are written into style elements.
+    ///
+    /// Default: [`OutputOptions::minified`] in release builds, [`OutputOptions::pretty`] otherwise.
+    pub fn output_options(mut self, value: OutputOptions) -> Self {
+        self.output_options = value;
+        self
+    }
+
+    /// Set the nonce of all `<style>` elements created by this manager.
+    ///
+    /// This allows the styles to be applied when the Content Security Policy of the page only
+    /// allows inline styles with this nonce. Styles rendered on the server use the nonce of the
+    /// `StaticWriter` if it has one.
+    pub fn nonce(mut self, value: impl Into<Cow<'static, str>>) -> Self {
+        self.nonce = Some(value.into());
+        self
+    }
+
+    /// Set the cache used to parse styles created with this manager.
+    ///
+    /// By default, the global [`SheetCache`](stylist_core::ast::SheetCache) is used.
+    #[cfg_attr(documenting, doc(cfg(feature = "parser")))]
+    #[cfg(feature = "parser")]
+    pub fn sheet_cache(mut self, value: stylist_core::ast::SheetCache) -> Self {
+        self.sheet_cache = Some(value);
+        self
+    }
+
     /// Build the [`StyleManager`].
     #[allow(unused_mut)]
     pub fn build(mut self) -> Result<StyleManager> {
@@ -100,6 +233,19 @@ impl StyleManagerBuilder {
             self.container = Some(doc_head()?.into());
         }
 
+        #[cfg(feature = "ssr")]
+        if let (Some(nonce), Some(style_data)) = (&self.nonce, &self.style_data) {
+            let mut style_data = style_data.lock().expect("failed to lock style data");
+            style_data.nonce.get_or_insert_with(|| nonce.clone());
+        }
+
+        #[cfg(feature = "ssr")]
+        if self.style_data.is_some() {
+            self.backend = Some(Rc::new(ssr::StaticBackend::new(self.backend.take())));
+        }
+        self.backend
+            .get_or_insert_with(|| Rc::new(DomBackend::default()));
+
         Ok(StyleManager {
             inner: Rc::new(self),
         })
@@ -146,27 +292,156 @@ impl StyleManager {
         self.inner.prefix.clone()
     }
 
+    /// The cascade layer that wraps all styles managed by this manager.
+    pub fn layer(&self) -> Option<Cow<'static, str>> {
+        self.inner.layer.clone()
+    }
+
+    /// The backend that mounts styles into the container.
+    pub fn backend(&self) -> Rc<dyn StyleBackend> {
+        self.inner
+            .backend
+            .clone()
+            .expect("backend is set when the manager is built")
+    }
+
+    /// The number of unused styles kept by this manager, `None` if unused styles are not
+    /// removed.
+    pub fn garbage_collect(&self) -> Option<usize> {
+        self.inner.max_unused
+    }
+
     /// The container [`Node`] for all style elements managed by this manager.
+    ///
+    /// This is always `None` with the `sync` feature on non-wasm targets.
     pub fn container(&self) -> Option<Node> {
-        self.inner.container.clone()
+        #[cfg(any(not(feature = "sync"), target_arch = "wasm32"))]
+        {
+            self.inner.container.clone()
+        }
+
+        #[cfg(all(feature = "sync", not(target_arch = "wasm32")))]
+        {
+            None
+        }
+    }
+
+    /// The nonce of `<style>` elements created by this manager.
+    pub fn nonce(&self) -> Option<Cow<'static, str>> {
+        self.inner.nonce.clone()
+    }
+
+    /// The options used to write styles managed by this manager.
+    pub fn output_options(&self) -> OutputOptions {
+        self.inner.output_options
+    }
+
+    /// The cache used to parse styles created with this manager.
+    #[cfg_attr(documenting, doc(cfg(feature = "parser")))]
+    #[cfg(feature = "parser")]
+    pub fn sheet_cache(&self) -> stylist_core::ast::SheetCache {
+        self.inner
+            .sheet_cache
+            .clone()
+            .unwrap_or_else(stylist_core::ast::SheetCache::global)
+    }
+
+    /// Runs `f` with the sheet cache of this manager, if any.
+    pub(crate) fn with_sheet_cache<F, R>(&self, f: F) -> R
+    where
+        F: FnOnce() -> R,
+    {
+        #[cfg(feature = "parser")]
+        if let Some(ref m) = self.inner.sheet_cache {
+            return m.scope(f);
+        }
+
+        f()
+    }
+
+    /// Renders the style string of a style with the given id.
+    fn render_style_str(&self, key: &StyleKey, id: &StyleId) -> String {
+        self.render_style_str_with_options(key, id, self.inner.output_options)
+    }
+
+    /// Renders the style string of a style with the given id and output options.
+    fn render_style_str_with_options(
+        &self,
+        key: &StyleKey,
+        id: &StyleId,
+        options: OutputOptions,
+    ) -> String {
+        // Non-global styles have ids prefixed in classes.
+        let class_name = (!key.is_global).then_some(id.as_ref());
+
+        match self.inner.layer {
+            Some(ref layer) => {
+                let sheet: Sheet = vec![ScopeContent::Rule(Rule {
+                    condition: vec!["@layer ".into(), layer.clone().into()].into(),
+                    content: key
+                        .ast
+                        .iter()
+                        .cloned()
+                        .map(RuleBlockContent::from)
+                        .collect(),
+                    span: None,
+                })]
+                .into();
+
+                sheet.to_style_str_with_options(class_name, options)
+            }
+            None => key.ast.to_style_str_with_options(class_name, options),
+        }
     }
 
     /// Returns the registry if it is availble, otherwise, creates the style and mounts it.
-    pub(crate) fn get_or_register_style(&self, key: StyleKey) -> Result<Rc<StyleContent>> {
+    ///
+    /// The registry is not locked while the class name generator and the backend are called, so
+    /// they can use the manager as well.
+    pub(crate) fn get_or_register_style(
+        &self,
+        key: StyleKey,
+        location: Option<&str>,
+    ) -> Result<Rc<StyleContent>> {
         let weak_mgr = self.downgrade();
-        let mut reg = self.inner.registry.borrow_mut();
 
-        if let Some(m) = reg.get(&key) {
-            return Ok(m);
-        }
+        // Styles rendered on the server keep the class name issued by the server.
+        let mut server_id = {
+            let mut reg = self.registry();
+            if let Some(m) = reg.get(&key) {
+                return Ok(m);
+            }
 
-        let id = match key.is_global {
-            true => StyleId::new_global(&key.prefix),
-            false => StyleId::new_scoped(&key.prefix),
+            reg.take_server_id(&key)
         };
 
-        // Non-global styles have ids prefixed in classes.
-        let style_str = key.ast.to_style_str((!key.is_global).then_some(&id));
+        // Different styles may still end up with the same id, we pick another one if this
+        // happens.
+        let mut attempt = 0;
+        // `Some` if the style has been rendered on the server, with whether it is served in an
+        // external stylesheet.
+        let (id, extracted) = loop {
+            let (id, extracted) = match server_id.take() {
+                Some((m, extracted)) => (m, Some(extracted)),
+                None => {
+                    let ctx = ClassNameContext {
+                        key: &key,
+                        location,
+                        attempt,
+                    };
+                    attempt += 1;
+                    let class_name = self.inner.class_name_generator.generate(&ctx);
+
+                    (StyleId::from_class_name(&class_name), None)
+                }
+            };
+
+            if self.registry().reserve_id(&id) {
+                break (id, extracted);
+            }
+        };
+
+        let style_str = self.render_style_str(&key, &id);
 
         // We parse the style str again in debug mode to ensure that interpolated values are
         // not corrupting the stylesheet.
@@ -175,32 +450,48 @@ impl StyleManager {
             .parse::<crate::ast::Sheet>()
             .expect_display("debug: Stylist failed to parse the style with interpolated values");
 
+        let ctx = MountContext {
+            manager: self,
+            key: &key,
+            id: &id,
+            style_str: &style_str,
+        };
+        let backend = self.backend();
+        let mounted = match extracted {
+            Some(_) if backend.hydrate(&ctx) => true,
+            // Styles served in an external stylesheet are not in the document.
+            Some(true) => false,
+            _ => match backend.mount(&ctx) {
+                Ok(()) => true,
+                Err(e) => {
+                    self.registry().release_id(&id);
+                    return Err(e);
+                }
+            },
+        };
+
         let content: Rc<_> = StyleContent {
-            id,
+            id: id.clone(),
             style_str,
+            mounted: mounted.then_some(backend),
             manager: weak_mgr,
             key: Rc::new(key),
+            // The handle being created.
+            handles: AtomicUsize::new(1),
         }
         .into();
 
-        #[cfg(feature = "ssr")]
-        {
-            if let Some(ref mut style_data) = self.style_data() {
-                // Automatically detach if has been used.
-                style_data.as_vec_mut().push(StyleDataContent {
-                    key: content.key().as_ref().clone(),
-                    id: content.id().clone(),
-                    style_str: content.get_style_str().to_string(),
-                });
-
-                // Register the created Style.
-                reg.register(content.clone());
-
-                return Ok(content);
-            }
+        let mut reg = self.registry();
+        // The same style may have been registered in the meantime, e.g.: by another thread.
+        if let Some(m) = reg.get(content.key()) {
+            drop(reg);
+            // Unmounts the style before its id can be used again.
+            drop(content);
+            self.registry().release_id(&id);
+
+            return Ok(m);
         }
 
-        self.mount(&content)?;
         // Register the created Style.
         reg.register(content.clone());
 
@@ -208,75 +499,86 @@ impl StyleManager {
     }
 
     pub(crate) fn unregister_style(&self, key: &Rc<StyleKey>) {
-        self.inner.registry.borrow_mut().unregister(key);
+        let removed = self.registry().unregister(key);
+        // Styles are unmounted when they are dropped.
+        drop(removed);
     }
 
-    /// Return a reference of style key.
-    #[cfg(test)]
-    fn get_registry(&self) -> &RefCell<StyleRegistry> {
-        &self.inner.registry
+    /// Locks the registry.
+    fn registry(&self) -> RegistryGuard<'_> {
+        RegistryGuard::new(self, self.inner.registry.lock())
     }
 
-    /// Mount the [`Style`](crate::Style) into the DOM tree.
-    #[cfg(target_arch = "wasm32")]
-    pub(crate) fn mount(&self, content: &StyleContent) -> Result<()> {
-        use crate::arch::document;
-        use crate::Error;
+    /// Called when the last handle of a style is dropped.
+    pub(crate) fn release_style(&self, content: &Rc<StyleContent>) {
+        if self.inner.max_unused.is_none() {
+            return;
+        }
 
-        let document = document()?;
-        let container = self.container().ok_or(Error::Web(None))?;
+        self.inner
+            .released
+            .lock()
+            .push((content.key().clone(), Rc::downgrade(content)));
+        self.collect_released();
+    }
 
-        (|| {
-            let style_element = document.create_element("style")?;
-            style_element.set_attribute("data-style", content.id())?;
-            style_element.set_text_content(Some(content.get_style_str()));
+    /// Marks released styles as unused.
+    ///
+    /// If the registry is in use, released styles are collected once it is no longer in use.
+    fn collect_released(&self) {
+        let max_unused = match self.inner.max_unused {
+            Some(m) => m,
+            None => return,
+        };
 
-            // Prepend element
-            if !self.inner.append {
-                if let Some(m) = container.first_child() {
-                    return m.insert_before(&style_element, Some(&m)).map(|_m| ());
-                }
+        loop {
+            let mut reg = match self.inner.registry.try_lock() {
+                Some(m) => m,
+                None => return,
+            };
+            let released = std::mem::take(&mut *self.inner.released.lock());
+            if released.is_empty() {
+                return;
             }
 
-            container.append_child(&style_element)?;
-            Ok(())
-        })()
-        .map_err(|e| Error::Web(Some(e)))
-    }
-
-    /// Unmount the [`Style`](crate::Style) from the DOM tree.
-    #[cfg(target_arch = "wasm32")]
-    pub(crate) fn unmount(id: &StyleId) -> Result<()> {
-        use crate::arch::document;
-        use crate::Error;
-
-        let document = document()?;
-        (|| {
-            if let Some(m) = document.query_selector(&format!("style[data-style={}]", id))? {
-                if let Some(parent) = m.parent_element() {
-                    parent.remove_child(&m)?;
+            let mut removed = Vec::new();
+            for (key, content) in released {
+                // The style may have been used again before it is collected.
+                let is_unused = reg
+                    .styles
+                    .get(&key)
+                    .is_some_and(|m| Rc::as_ptr(m) == content.as_ptr() && m.is_unused());
+                if is_unused {
+                    removed.extend(reg.release(&key, max_unused));
                 }
             }
 
-            Ok(())
-        })()
-        .map_err(|e| Error::Web(Some(e)))
+            // Styles are unmounted when they are dropped.
+            drop(reg);
+            drop(removed);
+        }
     }
 
-    /// Mount the [`Style`] in to the DOM tree.
-    #[cfg(not(target_arch = "wasm32"))]
-    #[allow(unused_variables)]
-    pub(crate) fn mount(&self, content: &StyleContent) -> Result<()> {
-        // Does nothing on non-wasm targets.
-        Ok(())
+    /// Return a reference of style key.
+    #[cfg(test)]
+    fn get_registry(&self) -> &Lock<StyleRegistry> {
+        &self.inner.registry
     }
 
-    /// Unmount the [`Style`] from the DOM tree.
-    #[cfg(not(target_arch = "wasm32"))]
-    #[allow(unused_variables)]
-    pub(crate) fn unmount(id: &StyleId) -> Result<()> {
-        // Does nothing on non-wasm targets.
-        Ok(())
+    /// The document that owns the container.
+    #[cfg(target_arch = "wasm32")]
+    fn document(&self) -> Result<web_sys::Document> {
+        use wasm_bindgen::JsCast;
+
+        use crate::arch::document;
+
+        match self.container() {
+            Some(m) => match m.dyn_into::<web_sys::Document>() {
+                Ok(m) => Ok(m),
+                Err(m) => m.owner_document().map(Ok).unwrap_or_else(document),
+            },
+            None => document(),
+        }
     }
 }
 
@@ -287,13 +589,51 @@ impl From<&Self> for StyleManager {
 }
 
 impl Default for StyleManager {
+    /// Returns the manager of the current [scope](StyleManager::scope), or the global default
+    /// manager if no scope is active.
     fn default() -> Self {
+        scope::current().unwrap_or_else(Self::global)
+    }
+}
+
+impl StyleManager {
+    /// Creates the global default manager.
+    fn build_global() -> Self {
+        #[allow(unused_mut)]
+        let mut builder = Self::builder();
+
+        // A server keeps its global default manager for its whole lifetime, styles that are not
+        // created in a scope would otherwise be kept forever.
+        #[cfg(all(feature = "ssr", not(target_arch = "wasm32")))]
+        {
+            builder = builder.garbage_collect(GLOBAL_MAX_UNUSED);
+        }
+
+        builder
+            .build()
+            .expect_display("Failed to create default manager.")
+    }
+
+    #[cfg(any(not(feature = "sync"), target_arch = "wasm32"))]
+    fn global() -> Self {
+        use once_cell::unsync::Lazy;
+
         thread_local! {
-            static MGR: Lazy<StyleManager> = Lazy::new(|| StyleManager::builder().build().expect_display("Failed to create default manager."));
+            static MGR: Lazy<StyleManager> = Lazy::new(StyleManager::build_global);
         }
 
         MGR.with(|m| (*m).clone())
     }
+
+    // The global default manager is shared by all threads.
+    #[cfg(all(feature = "sync", not(target_arch = "wasm32")))]
+    fn global() -> Self {
+        use once_cell::sync::Lazy;
+
+        static MGR: Lazy<StyleManager> = Lazy::new(StyleManager::build_global);
+
+        MGR.clone()
+    }
 }
 
 #[cfg(any(feature = "ssr", feature = "hydration"))]
@@ -306,10 +646,24 @@ mod feat_ssr_hydration {
 
     #[derive(Debug, Clone, Serialize, Deserialize)]
     pub(super) struct StyleDataContent {
-        pub key: StyleKey,
+        // The digest of the key of the style, the whole key is not sent to the client.
+        pub digest: u64,
         pub id: StyleId,
+        #[cfg(feature = "ssr")]
         #[serde(skip)]
         pub style_str: String,
+        // The minified style string, written into external stylesheets.
+        #[cfg(feature = "ssr")]
+        #[serde(skip)]
+        pub minified_str: String,
+    }
+
+    // The serialized form of `StyleData`.
+    #[derive(Serialize, Deserialize)]
+    struct StyleDataRepr<'a> {
+        styles: Cow<'a, [StyleDataContent]>,
+        #[serde(default)]
+        extracted: bool,
     }
 
     /// Data of Styles managed by the current style manager.
@@ -323,14 +677,26 @@ mod feat_ssr_hydration {
     /// If you are using [`ManagerProvider`](crate::yew::ManagerProvider),
     /// this behaviour is managed automatically.
     #[derive(Debug, Clone)]
-    pub struct StyleData(pub(super) Arc<Vec<StyleDataContent>>);
+    pub struct StyleData {
+        pub(super) styles: Arc<Vec<StyleDataContent>>,
+        // Set when the styles are written into an external stylesheet, so they are not expected
+        // to be in the document.
+        pub(super) extracted: bool,
+        // Only used to write the markup on the server, it is not serialized.
+        #[cfg(feature = "ssr")]
+        pub(super) nonce: Option<Cow<'static, str>>,
+    }
 
     impl Serialize for StyleData {
         fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
         where
             S: serde::Serializer,
         {
-            self.0.serialize(serializer)
+            StyleDataRepr {
+                styles: Cow::Borrowed(&self.styles),
+                extracted: self.extracted,
+            }
+            .serialize(serializer)
         }
     }
 
@@ -339,9 +705,12 @@ mod feat_ssr_hydration {
         where
             D: serde::Deserializer<'de>,
         {
-            Vec::<StyleDataContent>::deserialize(deserializer)
-                .map(Arc::new)
-                .map(Self)
+            StyleDataRepr::deserialize(deserializer).map(|m| Self {
+                styles: Arc::new(m.styles.into_owned()),
+                extracted: m.extracted,
+                #[cfg(feature = "ssr")]
+                nonce: None,
+            })
         }
     }
 }
@@ -351,50 +720,92 @@ pub use feat_ssr_hydration::*;
 
 #[cfg(feature = "hydration")]
 mod feat_hydration {
-    use super::*;
+    use std::collections::HashMap;
 
-    use crate::ast::ToStyleStr;
-    use std::collections::hash_map::Entry;
+    use super::*;
 
     impl StyleManager {
         /// Loads StyleData of current style manager.
         ///
+        /// Styles that are created afterwards get the class names they have been rendered with
+        /// on the server and take over the server rendered `<style>` elements. Styles that have
+        /// not been rendered on the server are mounted as usual.
+        ///
         /// # Note
         ///
         /// If you are using [`ManagerProvider`](crate::yew::ManagerProvider),
         /// this behaviour is managed automatically.
         ///
+        /// Styles that are not found in the document are mounted again, unless they are served in
+        /// a stylesheet written by `StyleData::write_stylesheet`.
+        ///
         /// # Panics
         ///
         /// This method should be called as early as possible.
         /// If the same style to be loaded already existed in the manager, it will panic.
         pub fn load_style_data(&self, data: &StyleData) {
-            let mut reg = self.inner.registry.borrow_mut();
+            let mut reg = self.registry();
 
-            for StyleDataContent { id, key, .. } in data.0.iter() {
-                let key = Rc::new(key.clone());
+            let registered: HashMap<u64, StyleId> = reg
+                .styles
+                .iter()
+                .map(|(k, m)| (k.digest(), m.id().clone()))
+                .collect();
 
-                match reg.styles.entry(key.clone()) {
-                    Entry::Occupied(m) => {
+            for StyleDataContent { digest, id, .. } in data.styles.iter() {
+                match registered.get(digest) {
+                    Some(m) => {
                         assert_eq!(
-                            m.get().id(),
+                            m,
                             id,
                             "An existing style has been rendered with a different class, this is not supported, please load style data first!"
                         );
                     }
-                    Entry::Vacant(m) => {
-                        m.insert(
-                            StyleContent {
-                                id: id.clone(),
-                                style_str: key.ast.to_style_str(Some(id)),
-                                manager: self.downgrade(),
-                                key: key.clone(),
-                            }
-                            .into(),
-                        );
-                    }
+                    // The style is hydrated when it is created.
+                    None => reg.add_server_id(*digest, id.clone(), data.extracted),
                 }
             }
         }
+
+        /// Moves the `<style>` elements streamed by a [`StreamingReader`] into the container of
+        /// this manager.
+        ///
+        /// The streamed elements are placed between the server rendered markup, this should be
+        /// called before the application is hydrated.
+        #[cfg(target_arch = "wasm32")]
+        pub fn collect_streamed_styles(&self) -> Result<()> {
+            use crate::Error;
+
+            let container = self.container().ok_or(Error::Web(None))?;
+            let elements = self
+                .document()?
+                .query_selector_all("style[data-style][data-streamed]")
+                .map_err(|e| Error::Web(Some(e)))?;
+
+            // Elements keep their order when they are prepended.
+            let first_child = container.first_child().filter(|_| !self.inner.append);
+            for element in (0..elements.length()).filter_map(|m| elements.item(m)) {
+                if element.parent_node().as_ref() == Some(&container) {
+                    continue;
+                }
+
+                container
+                    .insert_before(&element, first_child.as_ref())
+                    .map_err(|e| Error::Web(Some(e)))?;
+            }
+
+            Ok(())
+        }
+
+        /// Moves the `<style>` elements streamed by a [`StreamingReader`] into the container of
+        /// this manager.
+        ///
+        /// The streamed elements are placed between the server rendered markup, this should be
+        /// called before the application is hydrated.
+        #[cfg(not(target_arch = "wasm32"))]
+        pub fn collect_streamed_styles(&self) -> Result<()> {
+            // Does nothing on non-wasm targets.
+            Ok(())
+        }
     }
 }
@@ -1,27 +1,163 @@
-use std::collections::HashMap;
-use std::rc::Rc;
-
-use super::{StyleContent, StyleKey};
+use super::{StyleContent, StyleId, StyleKey, StyleManager};
+use crate::sync::{LockGuard, Rc};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::ops::{Deref, DerefMut};
 
 /// The style registry is a registry that keeps an instance of all styles for current manager.
 #[derive(Debug, Default)]
 pub(super) struct StyleRegistry {
     pub(crate) styles: HashMap<Rc<StyleKey>, Rc<StyleContent>>,
+    ids: HashSet<StyleId>,
+    // Styles without any handle, the least recently released first.
+    unused: VecDeque<Rc<StyleKey>>,
+    // The class names of styles rendered on the server by the digests of their keys, and whether
+    // the styles are served in an external stylesheet.
+    #[cfg(feature = "hydration")]
+    server_ids: HashMap<u64, (StyleId, bool)>,
 }
 
 impl StyleRegistry {
+    /// Registers a style, its id must have been reserved with [`reserve_id`](Self::reserve_id).
     pub fn register(&mut self, content: Rc<StyleContent>) {
+        if !self.ids.contains(content.id()) {
+            panic!("The StyleId of this Style has not been reserved.");
+        }
+
         if self.styles.insert(content.key().clone(), content).is_some() {
             panic!("A Style with this StyleKey has already been created.");
         }
     }
 
-    pub fn unregister(&mut self, key: &StyleKey) {
-        self.styles.remove(key);
+    /// Removes a style, it is returned so it can be unmounted after the registry is no longer
+    /// borrowed.
+    pub fn unregister(&mut self, key: &StyleKey) -> Option<Rc<StyleContent>> {
+        let content = self.styles.remove(key)?;
+        self.ids.remove(content.id());
+        self.unused.retain(|k| !Rc::ptr_eq(k, content.key()));
+
+        Some(content)
+    }
+
+    /// Reserves an id for a style that is being created.
+    ///
+    /// Returns `false` if the id is already used by another style.
+    pub fn reserve_id(&mut self, id: &StyleId) -> bool {
+        self.ids.insert(id.clone())
+    }
+
+    /// Releases an id reserved for a style that has not been registered.
+    pub fn release_id(&mut self, id: &StyleId) {
+        self.ids.remove(id);
+    }
+
+    /// Marks a style as unused and removes the least recently released unused styles until at
+    /// most `max_unused` of them are left.
+    ///
+    /// The removed styles are returned, so they can be unmounted after the registry is no longer
+    /// borrowed.
+    pub fn release(&mut self, key: &Rc<StyleKey>, max_unused: usize) -> Vec<Rc<StyleContent>> {
+        self.unused.retain(|k| !Rc::ptr_eq(k, key));
+        self.unused.push_back(key.clone());
+
+        let mut removed = Vec::new();
+        while self.unused.len() > max_unused {
+            let key = match self.unused.pop_front() {
+                Some(m) => m,
+                None => break,
+            };
+
+            // Styles that have been used again are kept.
+            let is_unused = self.styles.get(&key).is_some_and(|m| m.is_unused());
+            if is_unused {
+                if let Some(m) = self.styles.remove(&key) {
+                    self.ids.remove(m.id());
+                    removed.push(m);
+                }
+            }
+        }
+
+        removed
+    }
+
+    /// Adds the class name of a style rendered on the server.
+    #[cfg(feature = "hydration")]
+    pub fn add_server_id(&mut self, digest: u64, id: StyleId, extracted: bool) {
+        self.server_ids.insert(digest, (id, extracted));
     }
 
-    pub fn get(&self, key: &StyleKey) -> Option<Rc<StyleContent>> {
-        self.styles.get(key).cloned()
+    /// Returns the class name of a style rendered on the server and whether it is served in an
+    /// external stylesheet, `None` if it has not been rendered or its class name is already used
+    /// by another style.
+    ///
+    /// Always returns `None` if the hydration feature is disabled.
+    pub fn take_server_id(&mut self, key: &StyleKey) -> Option<(StyleId, bool)> {
+        #[cfg(feature = "hydration")]
+        {
+            if self.server_ids.is_empty() {
+                return None;
+            }
+
+            self.server_ids
+                .remove(&key.digest())
+                .filter(|(m, _)| !self.ids.contains(m))
+        }
+
+        #[cfg(not(feature = "hydration"))]
+        {
+            let _ = key;
+            None
+        }
+    }
+
+    /// Returns a style for a new handle.
+    pub fn get(&mut self, key: &StyleKey) -> Option<Rc<StyleContent>> {
+        let content = self.styles.get(key).cloned()?;
+        // The handle is counted before the registry is unlocked, so the style can not be
+        // collected in between.
+        content.retain();
+        if !self.unused.is_empty() {
+            self.unused.retain(|k| !Rc::ptr_eq(k, content.key()));
+        }
+
+        Some(content)
+    }
+}
+
+/// The locked registry of a manager.
+///
+/// Styles released while the registry is locked are collected after the guard is dropped.
+pub(super) struct RegistryGuard<'a> {
+    manager: &'a StyleManager,
+    inner: Option<LockGuard<'a, StyleRegistry>>,
+}
+
+impl<'a> RegistryGuard<'a> {
+    pub fn new(manager: &'a StyleManager, inner: LockGuard<'a, StyleRegistry>) -> Self {
+        Self {
+            manager,
+            inner: Some(inner),
+        }
+    }
+}
+
+impl Deref for RegistryGuard<'_> {
+    type Target = StyleRegistry;
+
+    fn deref(&self) -> &StyleRegistry {
+        self.inner.as_ref().expect("registry is locked")
+    }
+}
+
+impl DerefMut for RegistryGuard<'_> {
+    fn deref_mut(&mut self) -> &mut StyleRegistry {
+        self.inner.as_mut().expect("registry is locked")
+    }
+}
+
+impl Drop for RegistryGuard<'_> {
+    fn drop(&mut self) {
+        self.inner.take();
+        self.manager.collect_released();
     }
 }
 
@@ -49,7 +185,7 @@ mod tests {
         {
             let mgr = StyleManager::default();
             let reg = mgr.get_registry();
-            let reg = reg.borrow_mut();
+            let reg = reg.lock();
 
             log::debug!("{:#?}", reg);
         }
@@ -80,9 +216,9 @@ mod tests {
         {
             let mgr = StyleManager::default();
             let reg = mgr.get_registry();
-            let reg = reg.borrow_mut();
+            let reg = reg.lock();
 
-            assert!(reg.styles.get(style.key()).is_some());
+            assert!(reg.styles.contains_key(style.key()));
         }
 
         style.unregister();
@@ -90,9 +226,203 @@ mod tests {
         {
             let mgr = StyleManager::default();
             let reg = mgr.get_registry();
-            let reg = reg.borrow_mut();
+            let reg = reg.lock();
 
-            assert!(reg.styles.get(style.key()).is_none());
+            assert!(!reg.styles.contains_key(style.key()));
         }
     }
+
+    #[test]
+    #[cfg(feature = "parser")]
+    fn test_content_hash() {
+        use crate::manager::ContentHashClassNames;
+
+        init();
+
+        let create = |css: &str| {
+            let mgr = StyleManager::builder()
+                .class_name_generator(ContentHashClassNames)
+                .build()
+                .expect("Failed to create manager.");
+
+            Style::create_with_manager("hashed", css, mgr).expect("Failed to create Style.")
+        };
+
+        let style_a = create("color: red;");
+        let style_b = create("color: red;");
+        let style_c = create("color: blue;");
+
+        assert_eq!(style_a.get_class_name(), style_b.get_class_name());
+        assert_ne!(style_a.get_class_name(), style_c.get_class_name());
+        assert!(style_a.get_class_name().starts_with("hashed-"));
+        assert_eq!(style_a.get_class_name().len(), "hashed-".len() + 8);
+    }
+
+    #[test]
+    #[cfg(feature = "parser")]
+    fn test_class_name_collision() {
+        use crate::manager::{ClassNameContext, ClassNameGenerator};
+
+        init();
+
+        // Always returns the same class name unless it is taken.
+        #[derive(Debug)]
+        struct Fixed;
+
+        impl ClassNameGenerator for Fixed {
+            fn generate(&self, ctx: &ClassNameContext<'_>) -> String {
+                format!("{} {}", ctx.prefix(), ctx.attempt())
+            }
+        }
+
+        let mgr = StyleManager::builder()
+            .class_name_generator(Fixed)
+            .build()
+            .expect("Failed to create manager.");
+
+        let style_a = Style::create_with_manager("fixed prefix", "color: red;", mgr.clone())
+            .expect("Failed to create Style.");
+        let style_b = Style::create_with_manager("fixed prefix", "color: blue;", mgr)
+            .expect("Failed to create Style.");
+
+        assert_eq!(style_a.get_class_name(), "fixed-prefix-0");
+        assert_eq!(style_b.get_class_name(), "fixed-prefix-1");
+    }
+
+    #[test]
+    #[cfg(feature = "parser")]
+    fn test_garbage_collect() {
+        use crate::manager::StyleKey;
+
+        init();
+
+        let mgr = StyleManager::builder()
+            .garbage_collect(1)
+            .build()
+            .expect("Failed to create manager.");
+        let contains = |key: &StyleKey| mgr.get_registry().lock().styles.contains_key(key);
+
+        let style_a =
+            Style::new_with_manager("color: red;", &mgr).expect("Failed to create Style.");
+        let key_a = style_a.key().clone();
+        let style_b =
+            Style::new_with_manager("color: blue;", &mgr).expect("Failed to create Style.");
+        let key_b = style_b.key().clone();
+
+        // Styles with a handle are kept.
+        let clone_a = style_a.clone();
+        drop(style_a);
+        assert!(contains(&key_a));
+
+        // One unused style is kept.
+        drop(clone_a);
+        assert!(contains(&key_a));
+
+        // Styles that are used again are not collected.
+        let style_a =
+            Style::new_with_manager("color: red;", &mgr).expect("Failed to create Style.");
+        drop(style_b);
+        assert!(contains(&key_a));
+        assert!(contains(&key_b));
+
+        drop(style_a);
+        assert!(!contains(&key_b));
+        assert!(contains(&key_a));
+    }
+
+    #[test]
+    #[cfg(feature = "parser")]
+    fn test_release_while_locked() {
+        use crate::manager::StyleKey;
+
+        init();
+
+        let mgr = StyleManager::builder()
+            .garbage_collect(0)
+            .build()
+            .expect("Failed to create manager.");
+        let contains = |key: &StyleKey| mgr.get_registry().lock().styles.contains_key(key);
+
+        let style =
+            Style::new_with_manager("color: green;", &mgr).expect("Failed to create Style.");
+        let key = style.key().clone();
+
+        {
+            let reg = mgr.get_registry().lock();
+            drop(style);
+            assert!(reg.styles.contains_key(&key));
+        }
+
+        // Styles released while the registry is locked are collected by its next user.
+        let _style =
+            Style::new_with_manager("color: black;", &mgr).expect("Failed to create Style.");
+        assert!(!contains(&key));
+    }
+
+    #[test]
+    #[cfg(all(feature = "sync", feature = "parser"))]
+    fn test_sync_release() {
+        init();
+
+        let mgr = StyleManager::builder()
+            .garbage_collect(0)
+            .build()
+            .expect("Failed to create manager.");
+
+        let style =
+            Style::new_with_manager("color: purple;", &mgr).expect("Failed to create Style.");
+        let key = style.key().clone();
+
+        // Handles are created and dropped on other threads at the same time.
+        let handles: Vec<_> = (0..4)
+            .map(|_| {
+                let style = style.clone();
+                let mgr = mgr.clone();
+                std::thread::spawn(move || {
+                    for _ in 0..100 {
+                        drop(style.clone());
+                        Style::new_with_manager("color: purple;", &mgr)
+                            .expect("Failed to create Style.");
+                    }
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().expect("thread panicked");
+        }
+
+        // The last handle is released exactly once.
+        drop(style);
+        assert!(!mgr.get_registry().lock().styles.contains_key(&key));
+    }
+
+    #[test]
+    #[cfg(all(feature = "sync", feature = "parser"))]
+    fn test_sync() {
+        init();
+
+        let style = Style::new("color: orange;").expect("Failed to create Style.");
+        let class_name = style.get_class_name().to_string();
+
+        // Threads share the default manager.
+        let handles: Vec<_> = (0..4)
+            .map(|_| {
+                std::thread::spawn(|| {
+                    Style::new("color: orange;")
+                        .expect("Failed to create Style.")
+                        .get_class_name()
+                        .to_string()
+                })
+            })
+            .collect();
+        for handle in handles {
+            assert_eq!(handle.join().expect("thread panicked"), class_name);
+        }
+
+        // Styles can be sent to other threads.
+        let style = std::thread::spawn(move || style)
+            .join()
+            .expect("thread panicked");
+        assert_eq!(style.get_class_name(), class_name);
+    }
 }
@@ -0,0 +1,186 @@
+use std::cell::RefCell;
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use super::StyleManager;
+
+thread_local! {
+    static CURRENT: RefCell<Option<StyleManager>> = const { RefCell::new(None) };
+}
+
+/// Returns the manager of the innermost active scope on this thread.
+pub(super) fn current() -> Option<StyleManager> {
+    CURRENT.with(|m| m.borrow().clone())
+}
+
+// Restores the previous scope when dropped, even if the scoped code panics.
+struct Restore(Option<StyleManager>);
+
+impl Restore {
+    fn enter(manager: &StyleManager) -> Self {
+        Self(CURRENT.with(|m| m.borrow_mut().replace(manager.clone())))
+    }
+}
+
+impl Drop for Restore {
+    fn drop(&mut self) {
+        let prev = self.0.take();
+        CURRENT.with(move |m| *m.borrow_mut() = prev);
+    }
+}
+
+impl StyleManager {
+    /// Runs `f` with this manager as the [default](StyleManager::default) manager of this thread.
+    ///
+    /// Styles created without a manager in `f`, e.g.: with [`Style::new`](crate::Style::new) or
+    /// by `css!` outside of a [`ManagerProvider`](crate::yew::ManagerProvider), are registered
+    /// with this manager and are released with it, instead of being kept by the global default
+    /// manager.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stylist::manager::StyleManager;
+    /// use stylist::Style;
+    ///
+    /// let mgr = StyleManager::builder().prefix("request".into()).build()?;
+    ///
+    /// let style = mgr.scope(|| Style::new("color: red;"))?;
+    /// assert!(style.get_class_name().starts_with("request-"));
+    /// # Ok::<(), stylist::Error>(())
+    /// ```
+    pub fn scope<F, R>(&self, f: F) -> R
+    where
+        F: FnOnce() -> R,
+    {
+        let _restore = Restore::enter(self);
+
+        f()
+    }
+
+    /// Runs a future with this manager as the [default](StyleManager::default) manager.
+    ///
+    /// The manager is set each time the future is polled, so it follows the future when it is
+    /// moved to another thread by the executor. See [`scope`](Self::scope) for details.
+    pub fn scope_future<F>(&self, fut: F) -> ScopedFuture<F>
+    where
+        F: Future,
+    {
+        ScopedFuture {
+            manager: self.clone(),
+            inner: Box::pin(fut),
+        }
+    }
+}
+
+/// A future that runs with a request-scoped default manager.
+///
+/// It is created by [`StyleManager::scope_future`].
+#[derive(Debug)]
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct ScopedFuture<F> {
+    manager: StyleManager,
+    inner: Pin<Box<F>>,
+}
+
+impl<F> Future for ScopedFuture<F>
+where
+    F: Future,
+{
+    type Output = F::Output;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = &mut *self;
+        let _restore = Restore::enter(&this.manager);
+
+        this.inner.as_mut().poll(cx)
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "parser")]
+mod tests {
+    use std::sync::Arc;
+    use std::task::{Wake, Waker};
+
+    use super::*;
+    use crate::manager::RecordingBackend;
+    use crate::Style;
+
+    struct NoopWaker;
+
+    impl Wake for NoopWaker {
+        fn wake(self: Arc<Self>) {}
+    }
+
+    // Returns pending the first time it is polled.
+    #[derive(Default)]
+    struct YieldOnce(bool);
+
+    impl Future for YieldOnce {
+        type Output = ();
+
+        fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+            if self.0 {
+                return Poll::Ready(());
+            }
+            self.0 = true;
+            cx.waker().wake_by_ref();
+            Poll::Pending
+        }
+    }
+
+    fn create() -> (StyleManager, RecordingBackend) {
+        let backend = RecordingBackend::new();
+        let mgr = StyleManager::builder()
+            .backend(backend.clone())
+            .build()
+            .expect("Failed to create manager.");
+
+        (mgr, backend)
+    }
+
+    #[test]
+    fn test_scope() {
+        let (mgr, backend) = create();
+
+        let style = mgr
+            .scope(|| {
+                assert_eq!(StyleManager::default(), mgr);
+                // Scopes can be nested.
+                let (inner, _) = create();
+                inner.scope(|| assert_eq!(StyleManager::default(), inner));
+                assert_eq!(StyleManager::default(), mgr);
+
+                Style::new("color: red;")
+            })
+            .expect("Failed to create Style.");
+
+        assert_ne!(StyleManager::default(), mgr);
+        assert_eq!(backend.styles()[0].0, *style.id());
+    }
+
+    #[test]
+    fn test_scope_future() {
+        let (mgr, backend) = create();
+
+        let mut fut = mgr.scope_future(async {
+            YieldOnce::default().await;
+            Style::new("color: red;").expect("Failed to create Style.")
+        });
+
+        let waker = Waker::from(Arc::new(NoopWaker));
+        let mut cx = Context::from_waker(&waker);
+
+        assert!(Pin::new(&mut fut).poll(&mut cx).is_pending());
+        // The scope only lasts for a poll.
+        assert_ne!(StyleManager::default(), mgr);
+
+        let style = match Pin::new(&mut fut).poll(&mut cx) {
+            Poll::Ready(m) => m,
+            Poll::Pending => panic!("the future should be ready"),
+        };
+        assert_eq!(backend.styles()[0].0, *style.id());
+    }
+}
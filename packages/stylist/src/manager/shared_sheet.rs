@@ -0,0 +1,379 @@
+#[cfg(target_arch = "wasm32")]
+use std::cell::RefCell;
+#[cfg(target_arch = "wasm32")]
+use std::rc::Rc;
+
+/// Splits a style string into its top-level rules, so each one can be passed to `insertRule`.
+#[cfg(any(test, target_arch = "wasm32"))]
+pub(super) fn split_rules(style_str: &str) -> Vec<&str> {
+    let mut rules = Vec::new();
+
+    let mut start = 0;
+    let mut depth = 0usize;
+    let mut quote = None;
+    let mut chars = style_str.char_indices();
+
+    while let Some((index, c)) = chars.next() {
+        match (quote, c) {
+            (_, '\\') => {
+                chars.next();
+            }
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '"' | '\'') => quote = Some(c),
+            (None, '{') => depth += 1,
+            (None, '}') | (None, ';') => {
+                if c == '}' {
+                    depth = depth.saturating_sub(1);
+                }
+
+                if depth == 0 {
+                    let rule = style_str[start..=index].trim();
+                    if !rule.is_empty() {
+                        rules.push(rule);
+                    }
+                    start = index + 1;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    rules
+}
+
+/// Inserts all styles into a single stylesheet with `insertRule`.
+///
+/// This avoids creating an element for every style and is faster for applications with a lot of
+/// dynamic styles. Clones of the backend share the same stylesheet.
+///
+/// It does nothing on non-wasm targets.
+#[derive(Debug, Clone, Default)]
+pub struct SheetBackend {
+    #[cfg(target_arch = "wasm32")]
+    inner: Rc<RefCell<SharedSheet>>,
+}
+
+impl SheetBackend {
+    /// Inserts the styles into a `<style>` element in the container of the manager.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Inserts the styles into a constructed stylesheet that is added to the
+    /// `adoptedStyleSheets` of the container.
+    ///
+    /// The container should be a `ShadowRoot` or a `Document`, for other nodes the sheet is
+    /// adopted by their document. The same sheet can be shared by other shadow roots with
+    /// [`adopt`](Self::adopt).
+    pub fn adopted() -> Self {
+        Self {
+            #[cfg(target_arch = "wasm32")]
+            inner: Rc::new(RefCell::new(SharedSheet::new(true))),
+        }
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+mod feat_wasm {
+    use js_sys::Array;
+    use wasm_bindgen::{JsCast, JsValue};
+    use web_sys::{CssStyleSheet, Document, HtmlStyleElement, Node, ShadowRoot};
+
+    use super::{split_rules, SheetBackend};
+    use crate::arch::document;
+    use crate::manager::{MountContext, StyleBackend, StyleId};
+    use crate::Error;
+
+    // Where the stylesheet is attached.
+    #[derive(Debug)]
+    enum SheetOwner {
+        Element(HtmlStyleElement),
+        // The shadow roots and documents that adopted the sheet.
+        Adopted(Vec<Node>),
+    }
+
+    fn adopted_sheets(target: &Node) -> Option<Array> {
+        if let Some(m) = target.dyn_ref::<ShadowRoot>() {
+            return Some(m.adopted_style_sheets());
+        }
+
+        target
+            .dyn_ref::<Document>()
+            .map(|m| m.adopted_style_sheets())
+    }
+
+    fn set_adopted_sheets(target: &Node, sheets: &Array) {
+        if let Some(m) = target.dyn_ref::<ShadowRoot>() {
+            m.set_adopted_style_sheets(sheets);
+        } else if let Some(m) = target.dyn_ref::<Document>() {
+            m.set_adopted_style_sheets(sheets);
+        }
+    }
+
+    /// A stylesheet that all styles of a manager are inserted into with `insertRule`.
+    #[derive(Debug, Default)]
+    pub(super) struct SharedSheet {
+        adopted: bool,
+        sheet: Option<(CssStyleSheet, SheetOwner)>,
+        // The mounted styles in the order of their rules, with the number of rules each of them
+        // inserted.
+        styles: Vec<(StyleId, u32)>,
+    }
+
+    impl SharedSheet {
+        pub fn new(adopted: bool) -> Self {
+            Self {
+                adopted,
+                sheet: None,
+                styles: Vec::new(),
+            }
+        }
+
+        /// Returns the stylesheet, creating it if no style has been inserted yet.
+        fn sheet(
+            &mut self,
+            ctx: &MountContext<'_>,
+            document: &Document,
+        ) -> Result<CssStyleSheet, JsValue> {
+            let manager = ctx.manager();
+            let container = manager.container().ok_or(JsValue::UNDEFINED)?;
+
+            if self.adopted {
+                let sheet = self.constructed()?;
+                // The container is adopted when the first style is inserted, other roots may
+                // have adopted the sheet before.
+                if self.styles.is_empty() {
+                    self.adopt(document, &container)?;
+                }
+
+                return Ok(sheet);
+            }
+
+            if let Some((ref sheet, _)) = self.sheet {
+                return Ok(sheet.clone());
+            }
+
+            let element: HtmlStyleElement = document.create_element("style")?.unchecked_into();
+            element.set_attribute("data-style", &format!("{}-sheet", manager.prefix()))?;
+            if let Some(m) = manager.nonce() {
+                element.set_attribute("nonce", &m)?;
+            }
+            container.append_child(&element)?;
+
+            // The sheet is only available after the element has been attached.
+            let sheet: CssStyleSheet = element.sheet().ok_or(JsValue::UNDEFINED)?.unchecked_into();
+            self.sheet = Some((sheet.clone(), SheetOwner::Element(element)));
+
+            Ok(sheet)
+        }
+
+        // Returns the constructed stylesheet of an adopted sheet.
+        fn constructed(&mut self) -> Result<CssStyleSheet, JsValue> {
+            if let Some((ref sheet, _)) = self.sheet {
+                return Ok(sheet.clone());
+            }
+
+            let sheet = CssStyleSheet::new()?;
+            self.sheet = Some((sheet.clone(), SheetOwner::Adopted(Vec::new())));
+
+            Ok(sheet)
+        }
+
+        /// Adds the sheet to the adopted stylesheets of a shadow root or document.
+        ///
+        /// Other nodes adopt the sheet into the document they belong to.
+        pub fn adopt(&mut self, document: &Document, target: &Node) -> Result<(), JsValue> {
+            if !self.adopted {
+                return Ok(());
+            }
+            self.constructed()?;
+
+            let (sheet, roots) = match self.sheet {
+                Some((ref sheet, SheetOwner::Adopted(ref mut roots))) => (sheet, roots),
+                _ => return Ok(()),
+            };
+
+            let target = match adopted_sheets(target) {
+                Some(_) => target.clone(),
+                None => target
+                    .owner_document()
+                    .unwrap_or_else(|| document.clone())
+                    .unchecked_into(),
+            };
+            let sheets = adopted_sheets(&target).ok_or(JsValue::UNDEFINED)?;
+
+            if !sheets.includes(sheet, 0) {
+                // Adopted stylesheets may be a frozen array, so a new array is assigned.
+                set_adopted_sheets(&target, &sheets.concat(&Array::of1(sheet)));
+                roots.push(target);
+            }
+
+            Ok(())
+        }
+
+        /// Inserts the rules of a style.
+        pub fn insert(
+            &mut self,
+            ctx: &MountContext<'_>,
+            document: &Document,
+        ) -> Result<(), JsValue> {
+            let sheet = self.sheet(ctx, document)?;
+
+            let mut index = match ctx.prepend() {
+                true => 0,
+                false => self.styles.iter().map(|m| m.1).sum(),
+            };
+
+            let mut len = 0;
+            for rule in split_rules(ctx.style_str()) {
+                // Rules that the browser does not understand are skipped like they would be in a
+                // style element.
+                if sheet.insert_rule_with_index(rule, index).is_ok() {
+                    index += 1;
+                    len += 1;
+                }
+            }
+
+            match ctx.prepend() {
+                true => self.styles.insert(0, (ctx.id().clone(), len)),
+                false => self.styles.push((ctx.id().clone(), len)),
+            }
+
+            Ok(())
+        }
+
+        /// Deletes the rules of a style.
+        pub fn remove(&mut self, id: &StyleId) -> Result<(), JsValue> {
+            let pos = match self.styles.iter().position(|m| &m.0 == id) {
+                Some(m) => m,
+                None => return Ok(()),
+            };
+            let (_, len) = self.styles.remove(pos);
+            let start: u32 = self.styles[..pos].iter().map(|m| m.1).sum();
+
+            if let Some((ref sheet, _)) = self.sheet {
+                for index in (start..start + len).rev() {
+                    sheet.delete_rule(index)?;
+                }
+            }
+
+            Ok(())
+        }
+    }
+
+    impl Drop for SharedSheet {
+        fn drop(&mut self) {
+            match self.sheet {
+                Some((_, SheetOwner::Element(ref element))) => element.remove(),
+                Some((ref sheet, SheetOwner::Adopted(ref roots))) => {
+                    let sheet: &JsValue = sheet.as_ref();
+                    for root in roots.iter() {
+                        if let Some(m) = adopted_sheets(root) {
+                            set_adopted_sheets(root, &m.filter(&mut |m, _, _| &m != sheet));
+                        }
+                    }
+                }
+                None => {}
+            }
+        }
+    }
+
+    impl SheetBackend {
+        /// Adds the stylesheet to the `adoptedStyleSheets` of a `ShadowRoot` or a `Document`.
+        ///
+        /// This allows any number of shadow roots to share the same styles. It only has an
+        /// effect if the backend has been created with [`SheetBackend::adopted`].
+        pub fn adopt(&self, target: &Node) -> crate::Result<()> {
+            let document = document()?;
+
+            self.inner
+                .borrow_mut()
+                .adopt(&document, target)
+                .map_err(|e| Error::Web(Some(e)))
+        }
+    }
+
+    impl StyleBackend for SheetBackend {
+        fn mount(&self, ctx: &MountContext<'_>) -> crate::Result<()> {
+            let document = ctx.manager().document()?;
+
+            self.inner
+                .borrow_mut()
+                .insert(ctx, &document)
+                .map_err(|e| Error::Web(Some(e)))
+        }
+
+        fn unmount(&self, id: &StyleId) -> crate::Result<()> {
+            self.inner
+                .borrow_mut()
+                .remove(id)
+                .map_err(|e| Error::Web(Some(e)))
+        }
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+use feat_wasm::SharedSheet;
+
+#[cfg(not(target_arch = "wasm32"))]
+mod feat_no_wasm {
+    use web_sys::Node;
+
+    use super::SheetBackend;
+    use crate::manager::{MountContext, StyleBackend, StyleId};
+    use crate::Result;
+
+    impl SheetBackend {
+        /// Adds the stylesheet to the `adoptedStyleSheets` of a `ShadowRoot` or a `Document`.
+        ///
+        /// This allows any number of shadow roots to share the same styles. It only has an
+        /// effect if the backend has been created with [`SheetBackend::adopted`].
+        #[allow(unused_variables)]
+        pub fn adopt(&self, target: &Node) -> Result<()> {
+            // Does nothing on non-wasm targets.
+            Ok(())
+        }
+    }
+
+    impl StyleBackend for SheetBackend {
+        fn mount(&self, _ctx: &MountContext<'_>) -> Result<()> {
+            // Does nothing on non-wasm targets.
+            Ok(())
+        }
+
+        fn unmount(&self, _id: &StyleId) -> Result<()> {
+            // Does nothing on non-wasm targets.
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_rules() {
+        let style_str = r#"@layer base,components;
+.a {
+    content: "}";
+    color: red;
+}
+@media print {
+    .a {
+        color: black;
+    }
+}
+"#;
+
+        assert_eq!(
+            split_rules(style_str),
+            vec![
+                "@layer base,components;",
+                ".a {\n    content: \"}\";\n    color: red;\n}",
+                "@media print {\n    .a {\n        color: black;\n    }\n}",
+            ]
+        );
+    }
+}
@@ -3,6 +3,7 @@ use std::ops::DerefMut;
 use std::sync::{Arc, Mutex};
 
 use super::*;
+use crate::utils::{hash_to_alphanumeric, stable_hash};
 
 /// The reader to read styles from a [`StyleManager`].
 ///
@@ -23,6 +24,11 @@ impl StaticReader {
             .map(|m| m.clone())
             .unwrap_or_else(|_| StyleData::new())
     }
+
+    /// Turns this reader into a [`StreamingReader`] to write styles as html is streamed.
+    pub fn into_streaming(self) -> StreamingReader {
+        StreamingReader::new(self.inner)
+    }
 }
 
 /// The writer to be passed to [`StyleManager`] to write styles.
@@ -31,6 +37,20 @@ pub struct StaticWriter {
     inner: Arc<Mutex<StyleData>>,
 }
 
+impl StaticWriter {
+    /// Set the nonce of the `<style>` elements written by
+    /// [`StyleData::write_static_markup`].
+    ///
+    /// This overrides the nonce of the manager, so a new nonce can be used for every request.
+    pub fn nonce(self, value: impl Into<Cow<'static, str>>) -> Self {
+        if let Ok(mut m) = self.inner.lock() {
+            m.nonce = Some(value.into());
+        }
+
+        self
+    }
+}
+
 /// Creates a [StaticWriter] - [StaticReader] pair.
 pub fn render_static() -> (StaticWriter, StaticReader) {
     let inner = Arc::new(Mutex::new(StyleData::new()));
@@ -48,35 +68,158 @@ impl StyleData {
     where
         W: fmt::Write,
     {
-        for StyleDataContent { id, style_str, .. } in self.0.iter() {
-            // We cannot guarantee a valid class name if the user choose to use a custom prefix.
-            // If the default prefix is used, StyleId is guaranteed to be valid without
-            // escaping.
-            write!(w, r#"<style data-style="{}">"#, id)?;
-            write!(w, "{}", html_escape::encode_style(&style_str))?;
-            write!(w, "</style>")?;
+        write_styles(w, &self.styles, self.nonce.as_deref(), false)
+    }
+
+    /// Writes all styles as a single minified stylesheet and returns a hash of its content.
+    ///
+    /// The stylesheet can be served as a static file, the hash changes with its content so it can
+    /// be part of the file name and the file can be cached by browsers. Use
+    /// [`write_stylesheet_link`](Self::write_stylesheet_link) to link it from the page instead of
+    /// writing the styles with [`write_static_markup`](Self::write_static_markup).
+    ///
+    /// This marks the styles as served in an external stylesheet, so the client does not mount
+    /// them again when it does not find them in the document. The data should be sent to the
+    /// client after calling this method.
+    pub fn write_stylesheet<W>(&mut self, w: &mut W) -> std::result::Result<String, fmt::Error>
+    where
+        W: fmt::Write,
+    {
+        let stylesheet: String = self
+            .styles
+            .iter()
+            .map(|m| m.minified_str.as_str())
+            .collect();
+        w.write_str(&stylesheet)?;
+        self.extracted = true;
+
+        Ok(hash_to_alphanumeric(stable_hash(stylesheet.as_bytes())))
+    }
+
+    /// Writes a `<link rel="stylesheet">` tag to a stylesheet written by
+    /// [`write_stylesheet`](Self::write_stylesheet).
+    pub fn write_stylesheet_link<W>(&self, w: &mut W, href: &str) -> fmt::Result
+    where
+        W: fmt::Write,
+    {
+        write!(
+            w,
+            r#"<link rel="stylesheet" href="{}""#,
+            html_escape::encode_double_quoted_attribute(href)
+        )?;
+        if let Some(ref m) = self.nonce {
+            write!(
+                w,
+                r#" nonce="{}""#,
+                html_escape::encode_double_quoted_attribute(m)
+            )?;
         }
 
-        Ok(())
+        write!(w, ">")
     }
 }
 
+/// Writes a `<style>` tag for every style.
+///
+/// Streamed styles are marked with `data-streamed`, so they can be collected on the client.
+pub(super) fn write_styles<W>(
+    w: &mut W,
+    styles: &[StyleDataContent],
+    nonce: Option<&str>,
+    streamed: bool,
+) -> fmt::Result
+where
+    W: fmt::Write,
+{
+    for StyleDataContent { id, style_str, .. } in styles.iter() {
+        // Class names are escaped into valid identifiers when they are generated, so the id
+        // does not need to be escaped.
+        write!(w, r#"<style data-style="{}""#, id)?;
+        if streamed {
+            write!(w, " data-streamed")?;
+        }
+        if let Some(m) = nonce {
+            write!(
+                w,
+                r#" nonce="{}""#,
+                html_escape::encode_double_quoted_attribute(m)
+            )?;
+        }
+        write!(w, ">")?;
+        write!(w, "{}", html_escape::encode_style(&style_str))?;
+        write!(w, "</style>")?;
+    }
+
+    Ok(())
+}
+
 impl StyleManagerBuilder {
     /// Set the [StaticWriter] for current manager.
     ///
     /// # Note
     ///
     /// This also sets the StyleManager into the "static" mode. which it will stop rendering
-    /// styles into any html element.
+    /// styles into any html element. Styles are still passed to a [`StyleBackend`] set with
+    /// [`backend`](Self::backend), regardless of the order in which both are set.
     pub fn writer(mut self, w: StaticWriter) -> Self {
         self.style_data = Some(w.inner);
         self
     }
 }
 
+/// Collects styles into the [`StyleData`] of the [`StaticWriter`] of the manager.
+#[derive(Debug)]
+pub(super) struct StaticBackend {
+    // The backend set explicitly, if any.
+    inner: Option<Rc<dyn StyleBackend>>,
+}
+
+impl StaticBackend {
+    pub fn new(inner: Option<Rc<dyn StyleBackend>>) -> Self {
+        Self { inner }
+    }
+}
+
+impl StyleBackend for StaticBackend {
+    fn mount(&self, ctx: &MountContext<'_>) -> Result<()> {
+        if let Some(ref m) = self.inner {
+            m.mount(ctx)?;
+        }
+
+        let mut style_data = match ctx.manager().style_data() {
+            Some(m) => m,
+            None => return Ok(()),
+        };
+        let minified_str =
+            ctx.manager()
+                .render_style_str_with_options(ctx.key, ctx.id, OutputOptions::minified());
+
+        style_data.as_vec_mut().push(StyleDataContent {
+            digest: ctx.key.digest(),
+            id: ctx.id.clone(),
+            style_str: ctx.style_str.to_string(),
+            minified_str,
+        });
+
+        Ok(())
+    }
+
+    fn unmount(&self, id: &StyleId) -> Result<()> {
+        // Styles that have been rendered can not be removed.
+        match self.inner {
+            Some(ref m) => m.unmount(id),
+            None => Ok(()),
+        }
+    }
+}
+
 impl StyleData {
     pub(crate) fn new() -> StyleData {
-        StyleData(Arc::default())
+        StyleData {
+            styles: Arc::default(),
+            extracted: false,
+            nonce: None,
+        }
     }
 }
 
@@ -91,6 +234,201 @@ impl StyleManager {
 
 impl StyleData {
     pub(super) fn as_vec_mut(&mut self) -> &mut Vec<StyleDataContent> {
-        Arc::make_mut(&mut self.0)
+        Arc::make_mut(&mut self.styles)
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "parser")]
+mod tests {
+    use super::*;
+    use crate::Style;
+
+    #[test]
+    fn test_nonce() {
+        let (writer, reader) = render_static();
+        let mgr = StyleManager::builder()
+            .nonce("manager-nonce")
+            .writer(writer.nonce("request-nonce"))
+            .build()
+            .expect("Failed to create manager.");
+
+        let style = Style::new_with_manager("color: red;", mgr).expect("Failed to create Style.");
+
+        let mut markup = String::new();
+        reader
+            .read_style_data()
+            .write_static_markup(&mut markup)
+            .expect("Failed to write markup.");
+
+        assert!(markup.starts_with(&format!(
+            r#"<style data-style="{}" nonce="request-nonce">"#,
+            style.get_class_name()
+        )));
+    }
+
+    #[test]
+    fn test_writer_with_backend() {
+        let backend = RecordingBackend::new();
+
+        // Styles are collected and mounted, whichever is set first.
+        let managers = {
+            let (writer_a, reader_a) = render_static();
+            let (writer_b, reader_b) = render_static();
+
+            let mgr_a = StyleManager::builder()
+                .writer(writer_a)
+                .backend(backend.clone())
+                .build()
+                .expect("Failed to create manager.");
+            let mgr_b = StyleManager::builder()
+                .backend(backend.clone())
+                .writer(writer_b)
+                .build()
+                .expect("Failed to create manager.");
+
+            vec![(mgr_a, reader_a), (mgr_b, reader_b)]
+        };
+
+        for (mgr, reader) in managers {
+            let style =
+                Style::new_with_manager("color: red;", mgr).expect("Failed to create Style.");
+
+            let mut markup = String::new();
+            reader
+                .read_style_data()
+                .write_static_markup(&mut markup)
+                .expect("Failed to write markup.");
+
+            assert!(markup.contains(style.get_class_name()));
+            assert!(backend.styles().iter().any(|m| &m.0 == style.id()));
+        }
+    }
+
+    #[test]
+    fn test_write_stylesheet() {
+        let write = |css: &[&str]| {
+            let (writer, reader) = render_static();
+            let mgr = StyleManager::builder()
+                .output_options(OutputOptions::pretty())
+                .class_name_generator(ContentHashClassNames)
+                .writer(writer)
+                .build()
+                .expect("Failed to create manager.");
+
+            let styles: Vec<_> = css
+                .iter()
+                .map(|m| Style::new_with_manager(*m, &mgr).expect("Failed to create Style."))
+                .collect();
+
+            let mut stylesheet = String::new();
+            let hash = reader
+                .read_style_data()
+                .write_stylesheet(&mut stylesheet)
+                .expect("Failed to write stylesheet.");
+
+            (styles, stylesheet, hash)
+        };
+
+        let (styles, stylesheet, hash) = write(&["color: red;", "color: blue;"]);
+        assert_eq!(
+            stylesheet,
+            format!(
+                ".{}{{color:red}}.{}{{color:blue}}",
+                styles[0].get_class_name(),
+                styles[1].get_class_name()
+            )
+        );
+
+        assert_eq!(write(&["color: red;", "color: blue;"]).2, hash);
+        assert_ne!(write(&["color: red;"]).2, hash);
+    }
+
+    #[test]
+    fn test_write_stylesheet_link() {
+        let (writer, reader) = render_static();
+        StyleManager::builder()
+            .writer(writer.nonce("nonce"))
+            .build()
+            .expect("Failed to create manager.");
+
+        let mut markup = String::new();
+        reader
+            .read_style_data()
+            .write_stylesheet_link(&mut markup, "/styles.css?a&b")
+            .expect("Failed to write markup.");
+
+        assert_eq!(
+            markup,
+            r#"<link rel="stylesheet" href="/styles.css?a&amp;b" nonce="nonce">"#
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "hydration")]
+    fn test_hydrate() {
+        let (writer, reader) = render_static();
+        let server = StyleManager::builder()
+            .writer(writer)
+            .build()
+            .expect("Failed to create manager.");
+        let rendered =
+            Style::new_with_manager("color: red;", server).expect("Failed to create Style.");
+
+        let backend = RecordingBackend::new();
+        let client = StyleManager::builder()
+            .backend(backend.clone())
+            .build()
+            .expect("Failed to create manager.");
+        client.load_style_data(&reader.read_style_data());
+
+        // Styles rendered on the server keep their class names, they are mounted again if they
+        // are not found in the document.
+        let hydrated =
+            Style::new_with_manager("color: red;", &client).expect("Failed to create Style.");
+        assert_eq!(hydrated.get_class_name(), rendered.get_class_name());
+        assert_eq!(
+            backend.events(),
+            vec![BackendEvent::Mount(hydrated.id().clone())]
+        );
+
+        let style =
+            Style::new_with_manager("color: blue;", &client).expect("Failed to create Style.");
+        assert_eq!(
+            backend.events(),
+            vec![
+                BackendEvent::Mount(hydrated.id().clone()),
+                BackendEvent::Mount(style.id().clone())
+            ]
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "hydration")]
+    fn test_hydrate_extracted() {
+        let (writer, reader) = render_static();
+        let server = StyleManager::builder()
+            .writer(writer)
+            .build()
+            .expect("Failed to create manager.");
+        let rendered =
+            Style::new_with_manager("color: red;", server).expect("Failed to create Style.");
+
+        let mut data = reader.read_style_data();
+        data.write_stylesheet(&mut String::new())
+            .expect("Failed to write stylesheet.");
+
+        let backend = RecordingBackend::new();
+        let client = StyleManager::builder()
+            .backend(backend.clone())
+            .build()
+            .expect("Failed to create manager.");
+        client.load_style_data(&data);
+
+        // Styles served in an external stylesheet are not mounted again.
+        let hydrated =
+            Style::new_with_manager("color: red;", &client).expect("Failed to create Style.");
+        assert_eq!(hydrated.get_class_name(), rendered.get_class_name());
+        assert!(backend.events().is_empty());
     }
 }
@@ -0,0 +1,330 @@
+use std::fmt;
+use std::sync::{Arc, Mutex};
+
+use super::ssr::write_styles;
+use super::StyleData;
+
+// Elements whose content is not parsed as markup.
+const RAW_TEXT_ELEMENTS: &[&str] = &[
+    "iframe", "noembed", "noframes", "noscript", "script", "style", "textarea", "title", "xmp",
+];
+
+/// Where a streamed chunk of html ended.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+enum Markup {
+    /// Between tags, where a `<style>` tag can be inserted.
+    #[default]
+    Text,
+    /// Inside a tag, with its name, whether an attribute value may start, i.e.: just after `=`,
+    /// and the quote of the attribute value it is in.
+    Tag {
+        name: String,
+        name_done: bool,
+        after_eq: bool,
+        quote: Option<u8>,
+    },
+    /// Inside a comment, with the number of `-` just read.
+    Comment { dashes: usize },
+    /// Inside a raw text element, with its end tag, e.g.: `</script`, and the number of bytes of
+    /// it just read.
+    RawText { end_tag: String, matched: usize },
+    /// Inside a `<plaintext>` element, which has no end tag and lasts until the end of the
+    /// document.
+    PlainText,
+}
+
+impl Markup {
+    fn next(&mut self, c: u8) {
+        *self = match std::mem::take(self) {
+            Self::Text if c == b'<' => Self::Tag {
+                name: String::new(),
+                name_done: false,
+                after_eq: false,
+                quote: None,
+            },
+            Self::Text => Self::Text,
+
+            Self::Tag {
+                name, quote: None, ..
+            } if c == b'>' => {
+                let name = name.to_ascii_lowercase();
+                match name.as_str() {
+                    "plaintext" => Self::PlainText,
+                    m if RAW_TEXT_ELEMENTS.contains(&m) => Self::RawText {
+                        end_tag: format!("</{name}"),
+                        matched: 0,
+                    },
+                    _ => Self::Text,
+                }
+            }
+            Self::Tag {
+                mut name,
+                name_done: false,
+                after_eq,
+                quote,
+            } if (!c.is_ascii_whitespace() && c != b'/') || name.is_empty() => {
+                name.push(char::from(c));
+                match name.as_str() {
+                    "!--" => Self::Comment { dashes: 0 },
+                    _ => Self::Tag {
+                        name,
+                        name_done: false,
+                        after_eq,
+                        quote,
+                    },
+                }
+            }
+            Self::Tag {
+                name,
+                after_eq,
+                quote,
+                ..
+            } => {
+                // Quotes only start an attribute value right after `=`, they are part of the value
+                // anywhere else, e.g.: `<a href=x'y>`.
+                let (after_eq, quote) = match quote {
+                    Some(m) if m == c => (false, None),
+                    Some(m) => (false, Some(m)),
+                    None if after_eq && (c == b'"' || c == b'\'') => (false, Some(c)),
+                    None if after_eq && c.is_ascii_whitespace() => (true, None),
+                    None => (c == b'=', None),
+                };
+
+                Self::Tag {
+                    name,
+                    name_done: true,
+                    after_eq,
+                    quote,
+                }
+            }
+
+            Self::Comment { dashes } if c == b'>' && dashes >= 2 => Self::Text,
+            Self::Comment { dashes } if c == b'-' => Self::Comment { dashes: dashes + 1 },
+            Self::Comment { .. } => Self::Comment { dashes: 0 },
+
+            Self::RawText {
+                mut end_tag,
+                matched,
+            } => {
+                let matched = match end_tag.as_bytes()[matched] == c.to_ascii_lowercase() {
+                    true => matched + 1,
+                    false => usize::from(c == b'<'),
+                };
+
+                match matched == end_tag.len() {
+                    // The end tag continues as a tag named `/name`.
+                    true => Self::Tag {
+                        name: end_tag.split_off(1),
+                        name_done: true,
+                        after_eq: false,
+                        quote: None,
+                    },
+                    false => Self::RawText { end_tag, matched },
+                }
+            }
+
+            Self::PlainText => Self::PlainText,
+        };
+    }
+}
+
+/// A reader that writes styles as html is streamed.
+///
+/// The `<style>` tags of the styles registered since the previous chunk are inserted into the
+/// next chunk, ahead of the markup that uses them. Styles are only inserted between tags, a chunk
+/// that ends within a tag or a comment is never split.
+///
+/// On the client, the streamed styles should be moved into the container with
+/// [`StyleManager::collect_streamed_styles`](super::StyleManager::collect_streamed_styles) before
+/// the application is hydrated.
+///
+/// # Examples
+///
+/// ```
+/// use stylist::manager::{render_static, StyleManager};
+/// use stylist::Style;
+///
+/// let (writer, reader) = render_static();
+/// let mut reader = reader.into_streaming();
+/// let mgr = StyleManager::builder().writer(writer).build()?;
+///
+/// let mut html = String::new();
+/// reader.write_chunk(&mut html, "<div cla").unwrap();
+///
+/// let style = Style::new_with_manager("color: red;", &mgr)?;
+///
+/// // Styles are not written in the middle of a tag.
+/// let chunk = format!(r#"ss="{}">content</div>"#, style.get_class_name());
+/// reader.write_chunk(&mut html, &chunk).unwrap();
+/// assert!(html.starts_with(&format!(
+///     r#"<div class="{}"><style data-style="#,
+///     style.get_class_name()
+/// )));
+/// # Ok::<(), stylist::Error>(())
+/// ```
+#[derive(Debug)]
+pub struct StreamingReader {
+    inner: Arc<Mutex<StyleData>>,
+    // The number of styles that have been written.
+    written: usize,
+    markup: Markup,
+}
+
+impl StreamingReader {
+    pub(super) fn new(inner: Arc<Mutex<StyleData>>) -> Self {
+        Self {
+            inner,
+            written: 0,
+            markup: Markup::default(),
+        }
+    }
+
+    /// Writes a chunk of html, with the `<style>` tags of all styles that have been registered
+    /// since the previous chunk.
+    ///
+    /// If the chunk ends within a tag, the styles are written with a later chunk.
+    pub fn write_chunk<W>(&mut self, w: &mut W, chunk: &str) -> fmt::Result
+    where
+        W: fmt::Write,
+    {
+        let mut start = 0;
+        let mut pending = true;
+
+        // The styles are written at the first position between tags.
+        for (index, c) in chunk.bytes().enumerate() {
+            if pending && self.markup == Markup::Text {
+                w.write_str(&chunk[..index])?;
+                self.write_pending(w)?;
+
+                start = index;
+                pending = false;
+            }
+            self.markup.next(c);
+        }
+
+        w.write_str(&chunk[start..])
+    }
+
+    /// Writes the `<style>` tags of all styles that have been registered since they were last
+    /// written, e.g.: after the last chunk.
+    ///
+    /// This should only be called between tags.
+    pub fn write_pending<W>(&mut self, w: &mut W) -> fmt::Result
+    where
+        W: fmt::Write,
+    {
+        let data = match self.inner.lock() {
+            Ok(m) => m,
+            Err(_) => return Ok(()),
+        };
+        let pending = data.styles.get(self.written..).unwrap_or_default();
+        self.written = data.styles.len();
+
+        write_styles(w, pending, data.nonce.as_deref(), true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::manager::{StyleDataContent, StyleId};
+
+    // Returns the markup state after each chunk.
+    fn scan(chunks: &[&str]) -> Vec<Markup> {
+        let mut markup = Markup::default();
+        chunks
+            .iter()
+            .map(|m| {
+                m.bytes().for_each(|c| markup.next(c));
+                markup.clone()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_markup() {
+        let states = scan(&[
+            r#"<div class="a>"#,
+            r#"b">text</div><!--<[App]>"#,
+            r#"--><script>if (a < b) {}</scr"#,
+            r#"ipt><p>"#,
+        ]);
+
+        assert!(matches!(
+            states[0],
+            Markup::Tag {
+                quote: Some(b'"'),
+                ..
+            }
+        ));
+        assert!(matches!(states[1], Markup::Comment { .. }));
+        assert!(matches!(states[2], Markup::RawText { .. }));
+        assert_eq!(states[3], Markup::Text);
+
+        // Quotes within unquoted attribute values do not start a quoted value.
+        let states = scan(&[r#"<a href=x'y>"#, r#"<a title = "b>"#, r#"">"#]);
+        assert_eq!(states[0], Markup::Text);
+        assert!(matches!(
+            states[1],
+            Markup::Tag {
+                quote: Some(b'"'),
+                ..
+            }
+        ));
+        assert_eq!(states[2], Markup::Text);
+
+        let states = scan(&[
+            "<xmp><p>",
+            "</xmp><noscript><p>",
+            "</noscript><iframe>",
+            "</iframe>",
+            "<plaintext></plaintext><p>",
+        ]);
+        assert!(matches!(states[0], Markup::RawText { .. }));
+        assert!(matches!(states[1], Markup::RawText { .. }));
+        assert!(matches!(states[2], Markup::RawText { .. }));
+        assert_eq!(states[3], Markup::Text);
+        assert_eq!(states[4], Markup::PlainText);
+    }
+
+    #[test]
+    fn test_write_chunk() {
+        let data = Arc::new(Mutex::new(StyleData::new()));
+        let mut reader = StreamingReader::new(data.clone());
+
+        let push = |class_name: &str| {
+            let mut data = data.lock().expect("failed to lock style data");
+            data.as_vec_mut().push(StyleDataContent {
+                digest: 0,
+                id: StyleId::from_class_name(class_name),
+                style_str: ".a {}".into(),
+                minified_str: ".a{}".into(),
+            });
+        };
+
+        let mut html = String::new();
+        push("a");
+        // The style registered before the chunk is written ahead of it, the next one is written
+        // after the end of the tag.
+        reader
+            .write_chunk(&mut html, "<div cla")
+            .expect("Failed to write chunk.");
+        push("b");
+        reader
+            .write_chunk(&mut html, r#"ss="a">text"#)
+            .expect("Failed to write chunk.");
+        reader
+            .write_chunk(&mut html, "</div>")
+            .expect("Failed to write chunk.");
+
+        assert_eq!(
+            html,
+            concat!(
+                r#"<style data-style="a" data-streamed>.a {}</style>"#,
+                r#"<div class="a">"#,
+                r#"<style data-style="b" data-streamed>.a {}</style>"#,
+                "text</div>",
+            )
+        );
+    }
+}
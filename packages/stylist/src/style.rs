@@ -1,7 +1,7 @@
 use std::borrow::Cow;
-use std::rc::Rc;
 
 use crate::manager::{StyleContent, StyleId, StyleKey, StyleManager};
+use crate::sync::Rc;
 use crate::{Result, StyleSource};
 
 /// A struct that represents a scoped Style.
@@ -36,6 +36,8 @@ use crate::{Result, StyleSource};
 ///   the generated class name.
 /// - If a selector starts with a pseudo-class selector, it will be applied to the root element.
 /// - For other selectors, it will be prefixed with the generated class name.
+/// - If a block is nested in another block, the rules above apply with the selectors of the parent
+///   block in place of the generated class name.
 ///
 ///   Example, original style:
 ///
@@ -72,7 +74,13 @@ use crate::{Result, StyleSource};
 ///   ## Note:
 ///
 ///   Root pseudo class (`:root`) will also be treated like a Current Selector.
-#[derive(Debug, Clone)]
+///
+/// # Keyframes
+///
+/// The names of `@keyframes` rules are prefixed with the generated class name, as are references
+/// to them in `animation` and `animation-name` declarations of the same style. The generated name
+/// can be obtained with [`Style::keyframes_name`].
+#[derive(Debug)]
 pub struct Style {
     inner: Rc<StyleContent>,
 }
@@ -85,6 +93,7 @@ impl Style {
         css: StyleSource,
         manager: StyleManager,
     ) -> Result<Self> {
+        let location = css.location().map(str::to_string);
         let css = css.into_sheet();
 
         // Creates the StyleKey, return from registry if already cached.
@@ -94,7 +103,7 @@ impl Style {
             ast: css,
         };
 
-        let inner = manager.get_or_register_style(key)?;
+        let inner = manager.get_or_register_style(key, location.as_deref())?;
         let new_style = Self { inner };
 
         Ok(new_style)
@@ -158,7 +167,10 @@ impl Style {
         crate::Error: From<Css::Error>,
         M: Into<StyleManager>,
     {
-        Self::create_impl(class_prefix.into(), css.try_into()?, manager.into())
+        let mgr = manager.into();
+        let css = mgr.with_sheet_cache(|| css.try_into())?;
+
+        Self::create_impl(class_prefix.into(), css, mgr)
     }
 
     /// Returns the class name for current style
@@ -202,6 +214,34 @@ impl Style {
         self.inner.get_style_str()
     }
 
+    /// Get the generated name of a `@keyframes` rule defined in this style.
+    ///
+    /// Keyframes defined in a scoped style are renamed so they do not clash with keyframes of
+    /// other styles. `animation` and `animation-name` declarations in the same style are renamed
+    /// automatically. Names of keyframes that are not defined in this style are returned
+    /// unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stylist::Style;
+    ///
+    /// let style = Style::create(
+    ///     "my-component",
+    ///     "@keyframes fade { from { opacity: 0; } } animation: fade 1s;",
+    /// )?;
+    ///
+    /// // Example Output: my-component-uSu9NZZu-fade
+    /// println!("{}", style.keyframes_name("fade"));
+    /// # Ok::<(), stylist::Error>(())
+    /// ```
+    pub fn keyframes_name(&self, name: &str) -> String {
+        match self.inner.key().ast.keyframes().iter().any(|m| m == name) {
+            true => format!("{}-{}", self.get_class_name(), name),
+            false => name.to_string(),
+        }
+    }
+
     /// Return a reference of style key.
     #[cfg(test)]
     pub(crate) fn key(&self) -> &Rc<StyleKey> {
@@ -226,10 +266,35 @@ impl Style {
     }
 }
 
+impl Clone for Style {
+    fn clone(&self) -> Self {
+        self.inner.retain();
+
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl Drop for Style {
+    fn drop(&mut self) {
+        self.inner.release();
+    }
+}
+
 #[cfg(test)]
 #[cfg(feature = "parser")]
 mod tests {
     use super::*;
+    use crate::manager::OutputOptions;
+
+    // Styles are compared with the pretty output, which is not the default in release builds.
+    fn pretty_manager() -> StyleManager {
+        StyleManager::builder()
+            .output_options(OutputOptions::pretty())
+            .build()
+            .expect("Failed to create manager.")
+    }
 
     #[test]
     fn test_simple() {
@@ -238,7 +303,7 @@ mod tests {
 
     #[test]
     fn test_complex() {
-        let style = Style::new(
+        let style = Style::new_with_manager(
             r#"
                 background-color: black;
                 .with-class {
@@ -261,6 +326,7 @@ mod tests {
                     }
                 }
             "#,
+            pretty_manager(),
         )
         .expect("Failed to create Style.");
 
@@ -298,4 +364,103 @@ mod tests {
             )
         )
     }
+
+    #[test]
+    fn test_manager_layer() {
+        let mgr = StyleManager::builder()
+            .layer("stylist".into())
+            .output_options(OutputOptions::pretty())
+            .build()
+            .expect("Failed to create manager.");
+
+        let style = Style::new_with_manager(
+            r#"
+                color: red;
+                @media print {
+                    color: black;
+                }
+            "#,
+            mgr,
+        )
+        .expect("Failed to create Style.");
+
+        assert_eq!(
+            style.get_style_str(),
+            format!(
+                r#"@layer stylist {{
+    .{style_name} {{
+        color: red;
+    }}
+    @media print {{
+        .{style_name} {{
+            color: black;
+        }}
+    }}
+}}
+"#,
+                style_name = style.get_class_name()
+            )
+        );
+    }
+
+    #[test]
+    fn test_manager_sheet_cache() {
+        use stylist_core::ast::SheetCache;
+
+        let cache = SheetCache::new(8);
+        let mgr = StyleManager::builder()
+            .sheet_cache(cache.clone())
+            .build()
+            .expect("Failed to create manager.");
+
+        Style::new_with_manager("color: red;", mgr.clone()).expect("Failed to create Style.");
+        Style::new_with_manager("color: red;", mgr).expect("Failed to create Style.");
+
+        let stats = cache.stats();
+        assert_eq!(stats.misses, 1);
+        assert_eq!(stats.hits, 1);
+        assert_eq!(stats.len, 1);
+    }
+
+    #[test]
+    fn test_keyframes() {
+        let style = Style::new_with_manager(
+            r#"
+                @keyframes fade {
+                    from {
+                        opacity: 0;
+                    }
+                }
+                animation: fade 1s;
+            "#,
+            pretty_manager(),
+        )
+        .expect("Failed to create Style.");
+
+        assert_eq!(
+            style.get_style_str(),
+            format!(
+                r#"@keyframes {fade} {{
+    from {{
+        opacity: 0;
+    }}
+}}
+.{style_name} {{
+    animation: {fade} 1s;
+}}
+"#,
+                fade = style.keyframes_name("fade"),
+                style_name = style.get_class_name()
+            )
+        );
+    }
+
+    #[test]
+    fn test_keyframes_not_defined() {
+        let style = Style::new_with_manager("animation: fade 1s;", pretty_manager())
+            .expect("Failed to create Style.");
+
+        assert_eq!(style.keyframes_name("fade"), "fade");
+        assert!(style.get_style_str().contains("animation: fade 1s;"));
+    }
 }
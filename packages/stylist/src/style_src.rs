@@ -43,6 +43,18 @@ impl StyleSource {
         )
     }
 
+    /// The source location where this style source was created, if it was recorded.
+    pub(crate) fn location(&self) -> Option<&str> {
+        #[cfg(all(debug_assertions, feature = "debug_style_locations"))]
+        return Some(&self.location);
+        #[cfg(not(all(debug_assertions, feature = "debug_style_locations")))]
+        return None;
+    }
+
+    pub(crate) fn manager(&self) -> Option<&StyleManager> {
+        self.manager.as_ref()
+    }
+
     pub(crate) fn into_sheet(self) -> Sheet {
         self.inner
     }
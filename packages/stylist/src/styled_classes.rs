@@ -0,0 +1,177 @@
+use std::borrow::Cow;
+
+use crate::ast::Sheet;
+use crate::manager::StyleManager;
+use crate::{Result, Style, StyleSource};
+
+/// A [`StyleSource`] whose interpolated values are passed as CSS custom properties.
+///
+/// This struct is usually created by the [`css_vars!`](crate::css_vars) macro. The stylesheet
+/// refers to the values with `var(--name)`, so it stays the same when the values change and only
+/// one class is created for it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DynamicStyleSource {
+    source: StyleSource,
+    vars: Vec<(Cow<'static, str>, String)>,
+}
+
+impl DynamicStyleSource {
+    /// Creates a source from a stylesheet and the values of the custom properties it uses.
+    #[cfg_attr(all(debug_assertions, feature = "debug_style_locations"), track_caller)]
+    pub fn new(sheet: Sheet, vars: Vec<(Cow<'static, str>, String)>) -> Self {
+        Self {
+            source: sheet.into(),
+            vars,
+        }
+    }
+
+    /// The custom properties and their values.
+    pub fn vars(&self) -> &[(Cow<'static, str>, String)] {
+        &self.vars
+    }
+
+    #[doc(hidden)]
+    pub fn with_manager(mut self, manager: StyleManager) -> Self {
+        self.source = self.source.with_manager(manager);
+
+        self
+    }
+}
+
+/// A [`Style`] with the values of the CSS custom properties it uses.
+///
+/// The class name should be applied with the inline style returned by
+/// [`inline_style`](Self::inline_style) to the same element.
+///
+/// # Examples
+///
+/// ```
+/// use stylist::{css_vars, StyledClasses};
+///
+/// let width = 300;
+/// let styled = StyledClasses::new(css_vars!(width: ${width}px; color: red;))?;
+///
+/// // Example Output: --stylist-8f1c7d2e-0: 300px;
+/// println!("{}", styled.inline_style());
+/// # Ok::<(), stylist::Error>(())
+/// ```
+#[derive(Debug, Clone)]
+pub struct StyledClasses {
+    class: Style,
+    vars: Vec<(Cow<'static, str>, String)>,
+}
+
+impl StyledClasses {
+    /// Creates the style of a source with the default manager.
+    pub fn new(source: DynamicStyleSource) -> Result<Self> {
+        let manager = source.source.manager().cloned().unwrap_or_default();
+        Self::new_with_manager(source, manager)
+    }
+
+    /// Creates the style of a source using a custom manager.
+    pub fn new_with_manager<M>(source: DynamicStyleSource, manager: M) -> Result<Self>
+    where
+        M: Into<StyleManager>,
+    {
+        let DynamicStyleSource { source, vars } = source;
+
+        Ok(Self {
+            class: Style::new_with_manager(source, manager)?,
+            vars,
+        })
+    }
+
+    /// Returns the class name of the style.
+    pub fn get_class_name(&self) -> &str {
+        self.class.get_class_name()
+    }
+
+    /// Returns the style.
+    pub fn style(&self) -> &Style {
+        &self.class
+    }
+
+    /// The custom properties and their values.
+    pub fn vars(&self) -> &[(Cow<'static, str>, String)] {
+        &self.vars
+    }
+
+    /// The custom properties as the value of a `style` attribute.
+    ///
+    /// Characters that would end a declaration are escaped, so a value can never set other
+    /// properties.
+    pub fn inline_style(&self) -> String {
+        self.vars
+            .iter()
+            .map(|(name, value)| format!("{name}: {};", escape_value(value)))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+/// Escapes characters that would end a declaration value and closes unterminated strings and
+/// brackets.
+///
+/// Escape sequences of the value are kept as they are.
+fn escape_value(value: &str) -> Cow<'_, str> {
+    if !value.contains([';', '{', '}', '(', ')', '[', ']', '/', '\\', '"', '\'']) {
+        return value.into();
+    }
+
+    let mut escaped = String::with_capacity(value.len() + 4);
+    let mut quote = None;
+    let mut closing = Vec::new();
+    let mut chars = value.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            // A trailing backslash would escape the end of the declaration.
+            '\\' => {
+                escaped.push(c);
+                escaped.push(chars.next().unwrap_or('\\'));
+                continue;
+            }
+            ';' | '{' | '}' => escaped.push('\\'),
+            m if quote == Some(m) => quote = None,
+            _ if quote.is_some() => {}
+            '"' | '\'' => quote = Some(c),
+            '(' => closing.push(')'),
+            '[' => closing.push(']'),
+            ')' | ']' => match closing.last() {
+                Some(m) if *m == c => {
+                    closing.pop();
+                }
+                _ => escaped.push('\\'),
+            },
+            // A comment would hide the rest of the style attribute.
+            '/' if chars.peek() == Some(&'*') => escaped.push('\\'),
+            _ => {}
+        }
+
+        escaped.push(c);
+    }
+
+    escaped.extend(quote);
+    escaped.extend(closing.into_iter().rev());
+
+    escaped.into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_escape_value() {
+        assert_eq!(escape_value("100px"), "100px");
+        assert_eq!(escape_value("red; color: blue"), "red\\; color: blue");
+        assert_eq!(escape_value("}body{color:red"), "\\}body\\{color:red");
+        assert_eq!(escape_value(r#""a;b""#), r#""a\;b""#);
+        assert_eq!(escape_value(r#""\"; x"#), r#""\"\; x""#);
+        assert_eq!(escape_value(r"\3B \"), r"\3B \\");
+        assert_eq!(escape_value("url(; --b: 100px"), "url(\\; --b: 100px)");
+        assert_eq!(escape_value("calc([a) + 1px"), "calc([a\\) + 1px])");
+        assert_eq!(escape_value("1px) /* x"), "1px\\) \\/* x");
+        assert_eq!(escape_value(r#""(/*" 1px"#), r#""(/*" 1px"#);
+    }
+}
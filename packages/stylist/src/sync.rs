@@ -0,0 +1,85 @@
+//! Shared ownership and locking for the manager and its styles.
+//!
+//! With the `sync` feature, styles and managers are built on [`Arc`](std::sync::Arc) and
+//! [`Mutex`](std::sync::Mutex) so they can be sent to and shared between threads. It has no
+//! effect on wasm targets, where web-sys types are never thread-safe.
+
+#[cfg(any(not(feature = "sync"), target_arch = "wasm32"))]
+mod feat_unsync {
+    use std::cell::{RefCell, RefMut};
+
+    pub(crate) use std::rc::{Rc, Weak};
+
+    pub(crate) type LockGuard<'a, T> = RefMut<'a, T>;
+
+    /// A [`RefCell`], or a [`Mutex`](std::sync::Mutex) with the `sync` feature.
+    #[derive(Debug, Default)]
+    pub(crate) struct Lock<T> {
+        inner: RefCell<T>,
+    }
+
+    impl<T> Lock<T> {
+        pub fn lock(&self) -> LockGuard<'_, T> {
+            self.inner.borrow_mut()
+        }
+
+        /// Returns `None` if the lock is already held.
+        pub fn try_lock(&self) -> Option<LockGuard<'_, T>> {
+            self.inner.try_borrow_mut().ok()
+        }
+    }
+
+    /// Types that can be shared between threads with the `sync` feature.
+    ///
+    /// Without the `sync` feature, this is implemented for all types.
+    pub trait MaybeSync {}
+
+    impl<T: ?Sized> MaybeSync for T {}
+}
+
+#[cfg(any(not(feature = "sync"), target_arch = "wasm32"))]
+pub use feat_unsync::MaybeSync;
+#[cfg(any(not(feature = "sync"), target_arch = "wasm32"))]
+pub(crate) use feat_unsync::{Lock, LockGuard, Rc, Weak};
+
+#[cfg(all(feature = "sync", not(target_arch = "wasm32")))]
+mod feat_sync {
+    use std::sync::{Mutex, MutexGuard, TryLockError};
+
+    pub(crate) use std::sync::{Arc as Rc, Weak};
+
+    pub(crate) type LockGuard<'a, T> = MutexGuard<'a, T>;
+
+    /// A [`RefCell`](std::cell::RefCell), or a [`Mutex`] with the `sync` feature.
+    #[derive(Debug, Default)]
+    pub(crate) struct Lock<T> {
+        inner: Mutex<T>,
+    }
+
+    impl<T> Lock<T> {
+        pub fn lock(&self) -> LockGuard<'_, T> {
+            self.inner.lock().expect("failed to acquire lock")
+        }
+
+        /// Returns `None` if the lock is already held.
+        pub fn try_lock(&self) -> Option<LockGuard<'_, T>> {
+            match self.inner.try_lock() {
+                Ok(m) => Some(m),
+                Err(TryLockError::WouldBlock) => None,
+                Err(TryLockError::Poisoned(_)) => panic!("failed to acquire lock"),
+            }
+        }
+    }
+
+    /// Types that can be shared between threads with the `sync` feature.
+    ///
+    /// Without the `sync` feature, this is implemented for all types.
+    pub trait MaybeSync: Send + Sync {}
+
+    impl<T: ?Sized + Send + Sync> MaybeSync for T {}
+}
+
+#[cfg(all(feature = "sync", not(target_arch = "wasm32")))]
+pub use feat_sync::MaybeSync;
+#[cfg(all(feature = "sync", not(target_arch = "wasm32")))]
+pub(crate) use feat_sync::{Lock, LockGuard, Rc, Weak};
@@ -16,6 +16,31 @@ pub(crate) fn get_next_style_id() -> String {
     format!("style-{}", ctr)
 }
 
+/// Hashes bytes with 64-bit FNV-1a.
+///
+/// Unlike [`std::hash::Hash`], the result is the same on every platform and compiler version.
+pub(crate) fn stable_hash(bytes: &[u8]) -> u64 {
+    const OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+    const PRIME: u64 = 0x100000001b3;
+
+    bytes.iter().fold(OFFSET_BASIS, |hash, m| {
+        (hash ^ u64::from(*m)).wrapping_mul(PRIME)
+    })
+}
+
+/// Encodes the lowest bits of a hash as 8 alphanumeric characters.
+pub(crate) fn hash_to_alphanumeric(mut hash: u64) -> String {
+    const CHARS: &[u8] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz";
+
+    (0..8)
+        .map(|_| {
+            let c = CHARS[(hash % CHARS.len() as u64) as usize];
+            hash /= CHARS.len() as u64;
+            c as char
+        })
+        .collect()
+}
+
 pub(crate) fn get_entropy() -> String {
     #[cfg(feature = "random")]
     let entropy = get_rand_str();
@@ -36,4 +61,13 @@ mod tests {
         assert_ne!(get_next_style_id(), get_next_style_id());
         assert_ne!(get_next_style_id(), get_next_style_id());
     }
+
+    #[test]
+    fn test_stable_hash() {
+        // Known FNV-1a values, these must never change.
+        assert_eq!(stable_hash(b""), 0xcbf29ce484222325);
+        assert_eq!(stable_hash(b"a"), 0xaf63dc4c8601ec8c);
+        assert_eq!(hash_to_alphanumeric(0), "00000000");
+        assert_eq!(hash_to_alphanumeric(63), "11000000");
+    }
 }
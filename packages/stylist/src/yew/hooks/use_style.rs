@@ -28,7 +28,81 @@ where
     crate::Error: From<Css::Error>,
 {
     let mgr = use_context::<StyleManager>().unwrap_or_default();
+    let style = Style::new_with_manager(css, mgr).expect_display("failed to create style");
 
-    // It does not make sense to unmount a scoped style.
-    Style::new_with_manager(css, mgr).expect_display("failed to create style")
+    // The component holds the style of its latest render, so it is not collected while its
+    // class name is still rendered.
+    let held = use_mut_ref(|| None::<Style>);
+    *held.borrow_mut() = Some(style.clone());
+
+    style
+}
+
+#[cfg(test)]
+#[cfg(all(feature = "parser", not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use crate::manager::{BackendEvent, RecordingBackend};
+    use crate::yew::ManagerProvider;
+
+    #[derive(Properties, PartialEq)]
+    struct ManagerProps {
+        manager: StyleManager,
+    }
+
+    #[function_component]
+    fn Child(props: &ManagerProps) -> Html {
+        // The style is dropped when it is converted into classes.
+        let style = Style::new_with_manager("color: blue;", &props.manager)
+            .expect("Failed to create style.");
+        html! {<div class={style} />}
+    }
+
+    #[function_component]
+    fn Parent(props: &ManagerProps) -> Html {
+        let style = use_style("color: red;");
+        html! {<div class={style}><Child manager={props.manager.clone()} /></div>}
+    }
+
+    #[function_component]
+    fn App(props: &ManagerProps) -> Html {
+        html! {
+            <ManagerProvider manager={props.manager.clone()}>
+                <Parent manager={props.manager.clone()} />
+            </ManagerProvider>
+        }
+    }
+
+    #[tokio::test]
+    async fn test_garbage_collect() {
+        let backend = RecordingBackend::new();
+        let manager = StyleManager::builder()
+            .backend(backend.clone())
+            .garbage_collect(0)
+            .build()
+            .expect("Failed to create manager.");
+
+        yew::LocalServerRenderer::<App>::with_props(ManagerProps { manager })
+            .render()
+            .await;
+
+        // The style of the parent is held until the parent is destroyed, while the style of the
+        // child is released once it is converted into classes.
+        let events = backend.events();
+        let (parent, child) = match &events[..] {
+            [BackendEvent::Mount(parent), BackendEvent::Mount(child), ..] => {
+                (parent.clone(), child.clone())
+            }
+            m => panic!("unexpected events: {m:?}"),
+        };
+        assert_eq!(
+            events,
+            vec![
+                BackendEvent::Mount(parent.clone()),
+                BackendEvent::Mount(child.clone()),
+                BackendEvent::Unmount(child),
+                BackendEvent::Unmount(parent),
+            ]
+        );
+    }
 }
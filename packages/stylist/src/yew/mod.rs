@@ -97,7 +97,7 @@ pub use stylist_macros::use_style;
 
 use crate::ast::Sheet;
 use crate::manager::StyleManager;
-use crate::{Style, StyleSource};
+use crate::{Style, StyleSource, StyledClasses};
 
 use yew::html::ImplicitClone;
 
@@ -112,6 +112,11 @@ pub use provider::{ManagerProvider, ManagerProviderProps};
 
 pub use hooks::*;
 
+/// Converts a style into its class name.
+///
+/// The style is dropped after the conversion. If the manager collects unused styles, keep the
+/// [`Style`], e.g.: with [`use_style`](crate::yew::use_style), for as long as the class name is
+/// rendered.
 impl From<Style> for Classes {
     fn from(style: Style) -> Self {
         let mut classes = Self::new();
@@ -133,6 +138,30 @@ impl From<StyleSource> for Classes {
     }
 }
 
+impl From<&StyledClasses> for Classes {
+    fn from(styled: &StyledClasses) -> Self {
+        styled.style().clone().into()
+    }
+}
+
+impl From<StyledClasses> for Classes {
+    fn from(styled: StyledClasses) -> Self {
+        (&styled).into()
+    }
+}
+
+impl IntoPropValue<Classes> for &StyledClasses {
+    fn into_prop_value(self) -> Classes {
+        self.into()
+    }
+}
+
+impl IntoPropValue<Classes> for StyledClasses {
+    fn into_prop_value(self) -> Classes {
+        self.into()
+    }
+}
+
 impl IntoPropValue<Classes> for Style {
     fn into_prop_value(self) -> Classes {
         self.into()
@@ -57,6 +57,10 @@ pub fn manager_provider(props: &ManagerProviderProps) -> HtmlResult {
             // We must load the styles immediately before child components are rendered.
             let manager = manager.clone();
             use_memo(manager, move |manager| {
+                // Styles streamed between the server rendered markup are hydrated from the
+                // container.
+                let _result = manager.collect_streamed_styles();
+
                 if let Some(m) = _style_data {
                     manager.load_style_data(m.as_ref());
                 }
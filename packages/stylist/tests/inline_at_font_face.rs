@@ -0,0 +1,40 @@
+use stylist::manager::{OutputOptions, StyleManager};
+
+#[test]
+fn test_inline_at_font_face() {
+    let _ = env_logger::builder().is_test(true).try_init();
+    let manager = StyleManager::builder()
+        .output_options(OutputOptions::pretty())
+        .build()
+        .expect("Failed to create manager.");
+    manager.scope(|| {
+        let style = stylist::style! {
+            @font-face {
+                font-family: "Open Sans";
+                src: url("/fonts/OpenSans-Regular.woff2") format("woff2");
+            }
+            font-family: "Open Sans";
+            .title {
+                @font-face {
+                    font-family: "Title";
+                }
+            }
+        }
+        .unwrap();
+        let expected_result = format!(
+            r#"@font-face {{
+    font-family: "Open Sans";
+    src: url("/fonts/OpenSans-Regular.woff2") format("woff2");
+}}
+.{cls} {{
+    font-family: "Open Sans";
+}}
+@font-face {{
+    font-family: "Title";
+}}
+"#,
+            cls = style.get_class_name()
+        );
+        assert_eq!(expected_result, style.get_style_str());
+    });
+}
@@ -0,0 +1,36 @@
+use stylist::manager::{OutputOptions, StyleManager};
+
+#[test]
+fn test_inline_at_layer() {
+    let _ = env_logger::builder().is_test(true).try_init();
+    let manager = StyleManager::builder()
+        .output_options(OutputOptions::pretty())
+        .build()
+        .expect("Failed to create manager.");
+    manager.scope(|| {
+        let style = stylist::style! {
+            @layer base, components;
+            @layer base {
+                color: red;
+                .title {
+                    color: blue;
+                }
+            }
+        }
+        .unwrap();
+        let expected_result = format!(
+            r#"@layer base,components;
+@layer base {{
+    .{cls} {{
+        color: red;
+    }}
+    .{cls} .title {{
+        color: blue;
+    }}
+}}
+"#,
+            cls = style.get_class_name()
+        );
+        assert_eq!(expected_result, style.get_style_str());
+    });
+}
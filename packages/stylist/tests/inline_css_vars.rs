@@ -0,0 +1,89 @@
+use stylist::manager::{OutputOptions, StyleManager};
+use stylist::StyledClasses;
+
+#[test]
+fn test_css_vars() {
+    let _ = env_logger::builder().is_test(true).try_init();
+    let manager = StyleManager::builder()
+        .output_options(OutputOptions::pretty())
+        .build()
+        .expect("Failed to create manager.");
+    manager.scope(|| {
+        let create = |width: u32| {
+            StyledClasses::new(stylist::css_vars! {
+                width: ${width}px;
+                color: ${"red"};
+                .child {
+                    height: ${width / 2}px !important;
+                }
+            })
+            .unwrap()
+        };
+
+        let a = create(100);
+        let b = create(200);
+        assert_eq!(a.get_class_name(), b.get_class_name());
+
+        let var_width = &a.vars()[0].0;
+        let var_height = &a.vars()[1].0;
+        let expected_result = format!(
+            r#".{cls} {{
+    width: var({var_width});
+    color: red;
+}}
+.{cls} .child {{
+    height: var({var_height}) !important;
+}}
+"#,
+            cls = a.get_class_name()
+        );
+        assert_eq!(expected_result, a.style().get_style_str());
+        assert_eq!(
+            b.inline_style(),
+            format!("{var_width}: 200px; {var_height}: 100px;")
+        );
+    });
+}
+
+#[test]
+fn test_css_vars_escaped() {
+    let _ = env_logger::builder().is_test(true).try_init();
+    let color = "red; background: url(evil)";
+    let styled = StyledClasses::new(stylist::css_vars! {
+        color: ${color};
+    })
+    .unwrap();
+
+    let var_color = &styled.vars()[0].0;
+    assert_eq!(
+        styled.inline_style(),
+        format!("{var_color}: red\\; background: url(evil);")
+    );
+}
+
+#[test]
+fn test_css_vars_at_rules() {
+    let _ = env_logger::builder().is_test(true).try_init();
+    let manager = StyleManager::builder()
+        .output_options(OutputOptions::pretty())
+        .build()
+        .expect("Failed to create manager.");
+    manager.scope(|| {
+        let width = 100;
+        let styled = StyledClasses::new(stylist::css_vars! {
+            width: ${width}px;
+            @font-face {
+                font-family: ${"Custom"};
+                font-weight: ${width * 4};
+            }
+        })
+        .unwrap();
+
+        // Values in @font-face can not refer to custom properties of the element.
+        assert_eq!(styled.vars().len(), 1);
+        let var_width = &styled.vars()[0].0;
+        let style_str = styled.style().get_style_str();
+        assert!(style_str.contains(&format!("width: var({var_width});")));
+        assert!(style_str.contains("font-weight: 400;"));
+    });
+}
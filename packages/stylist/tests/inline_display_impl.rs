@@ -1,4 +1,6 @@
 use std::fmt::{Display, Formatter, Result};
+
+use stylist::manager::{OutputOptions, StyleManager};
 enum Foo {
     Bar,
 }
@@ -16,16 +18,22 @@ impl Foo {
 
 #[test]
 fn test_display_impl() {
-    let style = stylist::style! {
-        display: ${Foo::Bar};
-    }
-    .unwrap();
-    let expected_result = format!(
-        r#".{cls} {{
+    let manager = StyleManager::builder()
+        .output_options(OutputOptions::pretty())
+        .build()
+        .expect("Failed to create manager.");
+    manager.scope(|| {
+        let style = stylist::style! {
+            display: ${Foo::Bar};
+        }
+        .unwrap();
+        let expected_result = format!(
+            r#".{cls} {{
     display: none;
 }}
 "#,
-        cls = style.get_class_name()
-    );
-    assert_eq!(expected_result, style.get_style_str());
+            cls = style.get_class_name()
+        );
+        assert_eq!(expected_result, style.get_style_str());
+    });
 }
@@ -0,0 +1,74 @@
+use stylist::ast::{ComponentValue, RuleBlockContent, ScopeContent};
+use stylist::manager::{OutputOptions, StyleManager};
+
+#[test]
+fn test_important() {
+    let _ = env_logger::builder().is_test(true).try_init();
+    let manager = StyleManager::builder()
+        .output_options(OutputOptions::pretty())
+        .build()
+        .expect("Failed to create manager.");
+    manager.scope(|| {
+        let style = stylist::style! {
+            color: red !important;
+            .child {
+                margin: 0 auto !important;
+                width: ${100}px;
+            }
+        }
+        .unwrap();
+        let expected_result = format!(
+            r#".{cls} {{
+    color: red !important;
+}}
+.{cls} .child {{
+    margin: 0 auto !important;
+    width: 100px;
+}}
+"#,
+            cls = style.get_class_name()
+        );
+        assert_eq!(expected_result, style.get_style_str());
+    });
+}
+
+#[test]
+fn test_component_values() {
+    let width = 100;
+    let sheet = stylist::ast::sheet! {
+        margin: 0 auto !important;
+        width: ${width}px;
+    };
+
+    let attrs = match sheet[0] {
+        ScopeContent::Block(ref m) => m
+            .content
+            .iter()
+            .map(|m| match m {
+                RuleBlockContent::StyleAttr(ref m) => m,
+                _ => panic!("expected a style attribute"),
+            })
+            .collect::<Vec<_>>(),
+        _ => panic!("expected a block"),
+    };
+
+    // Values known at compile time are tokenized by the macro.
+    assert_eq!(
+        attrs[0].component_values.as_deref(),
+        Some(
+            &[
+                ComponentValue::Number("0".into()),
+                ComponentValue::Whitespace,
+                ComponentValue::Ident("auto".into()),
+            ][..]
+        )
+    );
+    assert_eq!(attrs[1].component_values, None);
+    assert_eq!(
+        attrs[1].component_values(),
+        vec![ComponentValue::Dimension {
+            value: "100".into(),
+            unit: "px".into(),
+        }]
+    );
+}
@@ -1,9 +0,0 @@
-fn main() {
-    let _ = stylist::css! {
-        .outer {
-            .inner {
-                background-color: red;
-            }
-        }
-    };
-}
@@ -0,0 +1,52 @@
+use stylist::manager::{OutputOptions, StyleManager};
+
+#[test]
+fn test_nested_blocks() {
+    let _ = env_logger::builder().is_test(true).try_init();
+    let manager = StyleManager::builder()
+        .output_options(OutputOptions::pretty())
+        .build()
+        .expect("Failed to create manager.");
+    manager.scope(|| {
+        let style = stylist::style! {
+            .card {
+                color: red;
+                .title {
+                    font-weight: bold;
+                }
+                &:hover {
+                    color: blue;
+                }
+                a:hover {
+                    color: green;
+                }
+                @media print {
+                    display: none;
+                }
+            }
+        }
+        .unwrap();
+        let expected_result = format!(
+            r#".{cls} .card {{
+    color: red;
+}}
+.{cls} .card .title {{
+    font-weight: bold;
+}}
+.{cls} .card:hover {{
+    color: blue;
+}}
+.{cls} .card a:hover {{
+    color: green;
+}}
+@media print {{
+    .{cls} .card {{
+        display: none;
+    }}
+}}
+"#,
+            cls = style.get_class_name()
+        );
+        assert_eq!(expected_result, style.get_style_str());
+    });
+}
@@ -0,0 +1,44 @@
+use stylist::manager::{OutputOptions, StyleManager};
+
+#[test]
+fn test_selectors() {
+    let _ = env_logger::builder().is_test(true).try_init();
+    let manager = StyleManager::builder()
+        .output_options(OutputOptions::pretty())
+        .build()
+        .expect("Failed to create manager.");
+    manager.scope(|| {
+        let style = stylist::style! {
+            a[href^="&"] {
+                color: red;
+            }
+            input[placeholder=":root"] {
+                color: blue;
+            }
+            li:not(&.active) > span {
+                color: green;
+            }
+            :is(h1, h2) + p {
+                margin-top: 0;
+            }
+        }
+        .unwrap();
+        let expected_result = format!(
+            r#".{cls} a[href^="&"] {{
+    color: red;
+}}
+.{cls} input[placeholder=":root"] {{
+    color: blue;
+}}
+li:not(.{cls}.active) > span {{
+    color: green;
+}}
+.{cls}:is(h1, h2) + p {{
+    margin-top: 0;
+}}
+"#,
+            cls = style.get_class_name()
+        );
+        assert_eq!(expected_result, style.get_style_str());
+    });
+}
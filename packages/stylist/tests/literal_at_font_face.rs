@@ -0,0 +1,43 @@
+use stylist::manager::{OutputOptions, StyleManager};
+
+#[test]
+fn test_literal_at_font_face() {
+    let manager = StyleManager::builder()
+        .output_options(OutputOptions::pretty())
+        .build()
+        .expect("Failed to create manager.");
+    manager.scope(|| {
+        let style = stylist::style! {
+            r#"@font-face {
+            font-family: "Open Sans";
+            src: url("/fonts/OpenSans-Regular.woff2") format("woff2");
+        }
+
+        @media print {
+            @font-face {
+                font-family: "Print";
+            }
+        }
+
+        font-family: "Open Sans";"#
+        }
+        .unwrap();
+        let expected_result = format!(
+            r#"@font-face {{
+    font-family: "Open Sans";
+    src: url("/fonts/OpenSans-Regular.woff2") format("woff2");
+}}
+@media print {{
+    @font-face {{
+        font-family: "Print";
+    }}
+}}
+.{cls} {{
+    font-family: "Open Sans";
+}}
+"#,
+            cls = style.get_class_name()
+        );
+        assert_eq!(expected_result, style.get_style_str());
+    });
+}
@@ -1,31 +1,39 @@
+use stylist::manager::{OutputOptions, StyleManager};
+
 #[test]
 fn test_sheet_value_by_ref() {
-    struct Theme {
-        color: String,
-    }
+    let manager = StyleManager::builder()
+        .output_options(OutputOptions::pretty())
+        .build()
+        .expect("Failed to create manager.");
+    manager.scope(|| {
+        struct Theme {
+            color: String,
+        }
 
-    let theme = Theme {
-        color: "red".into(),
-    };
+        let theme = Theme {
+            color: "red".into(),
+        };
 
-    let theme = &theme;
+        let theme = &theme;
 
-    use stylist::*;
-    let parsed = css!(
-        r#"
+        use stylist::*;
+        let parsed = css!(
+            r#"
             color: ${color};
         "#,
-        color = theme.color,
-    );
+            color = theme.color,
+        );
 
-    let style = Style::new(parsed).expect("Failed to parse style.");
+        let style = Style::new(parsed).expect("Failed to parse style.");
 
-    let expected = format!(
-        r#".{cls} {{
+        let expected = format!(
+            r#".{cls} {{
     color: red;
 }}
 "#,
-        cls = style.get_class_name()
-    );
-    assert_eq!(style.get_style_str(), expected);
+            cls = style.get_class_name()
+        );
+        assert_eq!(style.get_style_str(), expected);
+    });
 }
@@ -1,8 +1,15 @@
+use stylist::manager::{OutputOptions, StyleManager};
+
 #[test]
 fn test_sheet_interpolation() {
-    use stylist::*;
-    let parsed = css!(
-        r#"
+    let manager = StyleManager::builder()
+        .output_options(OutputOptions::pretty())
+        .build()
+        .expect("Failed to create manager.");
+    manager.scope(|| {
+        use stylist::*;
+        let parsed = css!(
+            r#"
             color: ${color};
 
             span, ${sel_div} {
@@ -17,16 +24,16 @@ fn test_sheet_interpolation() {
                 display: flex;
             }
         "#,
-        color = "red",
-        sel_div = "div.selected",
-        sel_root = "&.highlighted",
-        breakpoint = "(max-width: 500px)",
-    );
+            color = "red",
+            sel_div = "div.selected",
+            sel_root = "&.highlighted",
+            breakpoint = "(max-width: 500px)",
+        );
 
-    let style = Style::new(parsed).expect("Failed to parse style.");
+        let style = Style::new(parsed).expect("Failed to parse style.");
 
-    let expected = format!(
-        r#".{cls} {{
+        let expected = format!(
+            r#".{cls} {{
     color: red;
 }}
 .{cls} span, .{cls} div.selected {{
@@ -41,7 +48,8 @@ fn test_sheet_interpolation() {
     }}
 }}
 "#,
-        cls = style.get_class_name()
-    );
-    assert_eq!(style.get_style_str(), expected);
+            cls = style.get_class_name()
+        );
+        assert_eq!(style.get_style_str(), expected);
+    });
 }
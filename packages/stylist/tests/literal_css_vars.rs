@@ -0,0 +1,59 @@
+use stylist::manager::{OutputOptions, StyleManager};
+use stylist::StyledClasses;
+
+#[test]
+fn test_css_vars() {
+    let _ = env_logger::builder().is_test(true).try_init();
+    let manager = StyleManager::builder()
+        .output_options(OutputOptions::pretty())
+        .build()
+        .expect("Failed to create manager.");
+    manager.scope(|| {
+        let styled = StyledClasses::new(stylist::css_vars!(
+            r#"
+            width: ${width};
+            color: red;
+        "#,
+            width = "100px"
+        ))
+        .unwrap();
+
+        let var_width = &styled.vars()[0].0;
+        let expected_result = format!(
+            r#".{cls} {{
+    width: var({var_width});
+    color: red;
+}}
+"#,
+            cls = styled.get_class_name()
+        );
+        assert_eq!(expected_result, styled.style().get_style_str());
+        assert_eq!(styled.inline_style(), format!("{var_width}: 100px;"));
+    });
+}
+
+#[test]
+fn test_css_vars_at_rules() {
+    let _ = env_logger::builder().is_test(true).try_init();
+    let manager = StyleManager::builder()
+        .output_options(OutputOptions::pretty())
+        .build()
+        .expect("Failed to create manager.");
+    manager.scope(|| {
+        let styled = StyledClasses::new(stylist::css_vars!(
+            r#"
+            width: ${width};
+            @keyframes grow {
+                from {
+                    width: ${width};
+                }
+            }
+        "#,
+            width = "100px"
+        ))
+        .unwrap();
+
+        assert_eq!(styled.vars().len(), 1);
+        assert!(styled.style().get_style_str().contains("width: 100px;"));
+    });
+}
@@ -43,29 +43,44 @@ fn test_sheet_interpolation() {
             content: vec![StyleAttribute {
                 key: "background-color".into(),
                 value: vec!["red".into()].into(),
+                important: false,
+                component_values: None,
+                span: None,
             }
             .into()]
             .into(),
+            span: None,
         }),
         ScopeContent::Block(Block {
             condition: vec![
-                vec![".nested".into()].into(),
-                vec![".some-selector".into()].into(),
+                Selector::from(vec![CompoundSelector::from(vec![SimpleSelector::Class(
+                    vec!["nested".into()].into(),
+                )])]),
+                Selector::from(vec![CompoundSelector::from(vec![SimpleSelector::Raw(
+                    vec![".some-selector".into()].into(),
+                )])]),
             ]
             .into(),
             content: vec![
                 StyleAttribute {
                     key: "background-color".into(),
                     value: vec!["blue".into()].into(),
+                    important: false,
+                    component_values: None,
+                    span: None,
                 }
                 .into(),
                 StyleAttribute {
                     key: "width".into(),
                     value: vec!["100".into(), "px".into()].into(),
+                    important: false,
+                    component_values: None,
+                    span: None,
                 }
                 .into(),
             ]
             .into(),
+            span: None,
         }),
         ScopeContent::Rule(Rule {
             condition: vec!["@keyframes myframe".into()].into(),
@@ -76,8 +91,12 @@ fn test_sheet_interpolation() {
                         content: vec![RuleBlockContent::StyleAttr(StyleAttribute {
                             key: "width".into(),
                             value: vec!["100px".into()].into(),
+                            important: false,
+                            component_values: None,
+                            span: None,
                         })]
                         .into(),
+                        span: None,
                     }
                     .into(),
                 ),
@@ -87,13 +106,18 @@ fn test_sheet_interpolation() {
                         content: vec![RuleBlockContent::StyleAttr(StyleAttribute {
                             key: "width".into(),
                             value: vec!["200px".into()].into(),
+                            important: false,
+                            component_values: None,
+                            span: None,
                         })]
                         .into(),
+                        span: None,
                     }
                     .into(),
                 ),
             ]
             .into(),
+            span: None,
         }),
         ScopeContent::Rule(Rule {
             condition: vec!["@media screen and ".into(), "(max-width: 500px)".into()].into(),
@@ -103,13 +127,18 @@ fn test_sheet_interpolation() {
                     content: vec![StyleAttribute {
                         key: "background-color".into(),
                         value: vec!["brown".into()].into(),
+                        important: false,
+                        component_values: None,
+                        span: None,
                     }
                     .into()]
                     .into(),
+                    span: None,
                 }
                 .into(),
             )]
             .into(),
+            span: None,
         }),
     ]);
     assert_eq!(parsed, expected);
@@ -127,18 +156,24 @@ fn test_sheet_escaped() {
 
     let expected = Sheet::from(vec![ScopeContent::Block(Block {
         condition: vec![
-            vec![".nested".into()].into(),
-            Selector {
-                fragments: vec!["\"${var_a}\"".into()].into(),
-            },
+            Selector::from(vec![CompoundSelector::from(vec![SimpleSelector::Class(
+                vec!["nested".into()].into(),
+            )])]),
+            Selector::from(vec![CompoundSelector::from(vec![SimpleSelector::Raw(
+                vec!["\"${var_a}\"".into()].into(),
+            )])]),
         ]
         .into(),
         content: vec![StyleAttribute {
             key: "content".into(),
             value: vec!["\"${var_b}\"".into()].into(),
+            important: false,
+            component_values: None,
+            span: None,
         }
         .into()]
         .into(),
+        span: None,
     })]);
     assert_eq!(parsed, expected);
 }